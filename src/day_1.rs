@@ -1,5 +1,4 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
 
 use std::collections::HashMap;
 
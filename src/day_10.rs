@@ -1,5 +1,4 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
 
 pub fn solve_the_puzzle_10_1() {
     match file::read_file("inputs/day_10_1.txt") {
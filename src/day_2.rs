@@ -1,10 +1,9 @@
-#[path = "utils/file.rs"]
-mod file;
-#[path = "utils/number.rs"]
-mod number;
+use crate::utils::file;
+use crate::utils::number;
+use crate::utils::parse::{self, Cursor, ParseError};
 
+use std::fmt;
 use std::str::FromStr;
-use std::collections::HashMap;
 
 /*
 --- Day 2: I Was Told There Would Be No Math ---
@@ -71,61 +70,415 @@ game 4 would also have been impossible because the Elf showed you 15 blue cubes
 If you add up the IDs of the games that would have been possible, you get 8.
 Determine which games would have been possible if the bag had been loaded with only 12 red cubes, 13 green cubes, and 14 blue cubes. What is the sum of the IDs of those games?
 */
-pub fn solve_the_puzzle_2_2() {
-    const GREEN: i32 = 13;
-    const RED: i32 = 12;
-    const BLUE: i32 = 14;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeCount {
+    pub count: u32,
+    pub colour: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reveal {
+    pub cubes: Vec<CubeCount>,
+}
+
+impl Reveal {
+    pub fn count_of(&self, colour: &str) -> u32 {
+        self.cubes
+            .iter()
+            .find(|cube| cube.colour == colour)
+            .map_or(0, |cube| cube.count)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub reveals: Vec<Reveal>,
+}
+
+impl Game {
+    /// Largest number of cubes of `colour` shown in any single reveal of this game.
+    pub fn max_of(&self, colour: &str) -> u32 {
+        self.reveals.iter().map(|reveal| reveal.count_of(colour)).max().unwrap_or(0)
+    }
+}
+
+fn parse_reveal(cursor: &mut Cursor) -> Result<Reveal, ParseError> {
+    let mut cubes: Vec<CubeCount> = vec![];
+    loop {
+        cursor.skip_spaces();
+        let count = cursor.number()?;
+        cursor.skip_spaces();
+        let start = cursor.pos();
+        let colour = cursor.word().map_err(|_| cursor.error("expected a colour"))?;
+        if cubes.iter().any(|cube| cube.colour == colour) {
+            cursor.seek(start);
+            return Err(cursor.error(format!("colour {} is revealed twice", colour)));
+        }
+        cubes.push(CubeCount { count, colour: colour.to_string() });
+        cursor.skip_spaces();
+        if !cursor.eat(',') {
+            return Ok(Reveal { cubes });
+        }
+    }
+}
+
+/// Parses a single `Game N: ...` record. `line` is only used for error positions.
+pub fn parse_game(text: &str, line: usize) -> Result<Game, ParseError> {
+    let mut cursor = Cursor::new(text, line);
+    cursor.skip_spaces();
+    cursor.expect("Game")?;
+    cursor.skip_spaces();
+    let id = cursor.number()?;
+    cursor.skip_spaces();
+    cursor.expect(":")?;
+    let mut reveals = vec![parse_reveal(&mut cursor)?];
+    while cursor.eat(';') {
+        reveals.push(parse_reveal(&mut cursor)?);
+    }
+    if !cursor.is_at_end() {
+        return Err(cursor.error("expected ',', ';' or end of line"));
+    }
+    Ok(Game { id, reveals })
+}
+
+/// Parses every non-empty line of the input, rejecting games whose id was already seen.
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    let mut games: Vec<Game> = vec![];
+    for (index, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        let game = parse_game(text, index + 1)?;
+        if games.iter().any(|g| g.id == game.id) {
+            return Err(ParseError {
+                line: index + 1,
+                column: 1,
+                message: format!("duplicate game id {}", game.id),
+            });
+        }
+        games.push(game);
+    }
+    Ok(games)
+}
+
+/// Cube limits per named colour. Order is the order the colours were configured in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bag {
+    pub cubes: Vec<CubeCount>,
+}
+
+impl Default for Bag {
+    /// The bag from the puzzle statement: 12 red, 13 green and 14 blue cubes.
+    fn default() -> Bag {
+        Bag {
+            cubes: vec![
+                CubeCount { count: 12, colour: "red".to_string() },
+                CubeCount { count: 13, colour: "green".to_string() },
+                CubeCount { count: 14, colour: "blue".to_string() },
+            ],
+        }
+    }
+}
+
+/// Error raised while reading a bag configuration; `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBagError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseBagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bag line {}: {}", self.line, self.message)
+    }
+}
 
+/// Raised in strict mode when a game reveals a colour the bag does not know about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownColourError {
+    pub game: u32,
+    pub colour: String,
+}
+
+impl fmt::Display for UnknownColourError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "game {} reveals unknown colour {}", self.game, self.colour)
+    }
+}
+
+/// Why the sum of powers has no answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PowerError {
+    UnknownColour(UnknownColourError),
+    /// A game's power, or the sum of all of them, does not fit in a `u64`.
+    Overflow(String),
+}
+
+impl fmt::Display for PowerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PowerError::UnknownColour(error) => error.fmt(f),
+            PowerError::Overflow(what) => write!(f, "{} overflows u64", what),
+        }
+    }
+}
+
+impl From<UnknownColourError> for PowerError {
+    fn from(error: UnknownColourError) -> PowerError {
+        PowerError::UnknownColour(error)
+    }
+}
+
+/// Splits a bag specification into `(line, colour, value)` triples, checking that every
+/// colour is a single word configured at most once.
+fn parse_colour_entries(spec: &str) -> Result<Vec<(usize, &str, &str)>, ParseBagError> {
+    let mut entries: Vec<(usize, &str, &str)> = vec![];
+    for (index, line) in spec.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        for entry in line.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let error = |message: String| ParseBagError { line: index + 1, message };
+            let (colour, value) = entry
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected colour=count, found '{}'", entry)))?;
+            let colour = colour.trim();
+            if colour.is_empty() || !colour.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(error(format!("invalid colour '{}'", colour)));
+            }
+            if entries.iter().any(|(_, c, _)| *c == colour) {
+                return Err(error(format!("colour {} is configured twice", colour)));
+            }
+            entries.push((index + 1, colour, value.trim()));
+        }
+    }
+    if entries.is_empty() {
+        return Err(ParseBagError { line: 1, message: "no colours configured".to_string() });
+    }
+    Ok(entries)
+}
+
+impl Bag {
+    /// Parses `colour=count` entries separated by commas or newlines, as given on the
+    /// command line (`red=12,green=13,blue=14`) or in a config file. `#` starts a comment.
+    pub fn parse(spec: &str) -> Result<Bag, ParseBagError> {
+        let mut cubes: Vec<CubeCount> = vec![];
+        for (line, colour, count) in parse_colour_entries(spec)? {
+            let count: u32 = count.parse().map_err(|_| ParseBagError {
+                line,
+                message: format!("invalid count '{}' for {}", count, colour),
+            })?;
+            cubes.push(CubeCount { count, colour: colour.to_string() });
+        }
+        Ok(Bag { cubes })
+    }
+
+    pub fn limit_of(&self, colour: &str) -> Option<u32> {
+        self.cubes.iter().find(|cube| cube.colour == colour).map(|cube| cube.count)
+    }
+
+    /// In strict mode, fails on the first revealed colour that is not configured.
+    pub fn check(&self, game: &Game, strict: bool) -> Result<(), UnknownColourError> {
+        if !strict {
+            return Ok(());
+        }
+        for cube in game.reveals.iter().flat_map(|reveal| reveal.cubes.iter()) {
+            if self.limit_of(&cube.colour).is_none() {
+                return Err(UnknownColourError { game: game.id, colour: cube.colour.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// A game is possible when no reveal shows more cubes of a configured colour than the bag
+    /// holds. Colours the bag does not know about are ignored; use `check` to reject them.
+    pub fn allows(&self, game: &Game) -> bool {
+        self.cubes.iter().all(|cube| game.max_of(&cube.colour) <= cube.count)
+    }
+
+    /// Product of the fewest cubes of each configured colour that make the game possible, or
+    /// `None` if it does not fit in a `u64`.
+    pub fn power(&self, game: &Game) -> Option<u64> {
+        number::checked_product(self.cubes.iter().map(|cube| game.max_of(&cube.colour) as u64))
+    }
+}
+
+pub fn sum_of_possible_ids(games: &[Game], bag: &Bag, strict: bool) -> Result<u32, UnknownColourError> {
+    let mut result = 0;
+    for game in games {
+        bag.check(game, strict)?;
+        if bag.allows(game) {
+            result += game.id;
+        }
+    }
+    Ok(result)
+}
+
+pub fn sum_of_powers(games: &[Game], bag: &Bag, strict: bool) -> Result<u64, PowerError> {
+    let mut powers: Vec<u64> = vec![];
+    for game in games {
+        bag.check(game, strict)?;
+        let power = bag.power(game).ok_or_else(|| PowerError::Overflow(format!("power of game {}", game.id)))?;
+        powers.push(power);
+    }
+    number::checked_sum(powers).ok_or_else(|| PowerError::Overflow("sum of powers".to_string()))
+}
+
+/// Inclusive lower and upper cube limits per colour, e.g. `red=10..14,green=13,blue=12..20`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BagRange {
+    pub low: Bag,
+    pub high: Bag,
+}
+
+impl BagRange {
+    pub fn parse(spec: &str) -> Result<BagRange, ParseBagError> {
+        let mut low: Vec<CubeCount> = vec![];
+        let mut high: Vec<CubeCount> = vec![];
+        for (line, colour, range) in parse_colour_entries(spec)? {
+            let error = |message: String| ParseBagError { line, message };
+            let (from, to) = range.split_once("..").unwrap_or((range, range));
+            let from: u32 = from.trim().parse().map_err(|_| error(format!("invalid range '{}' for {}", range, colour)))?;
+            let to: u32 = to.trim().parse().map_err(|_| error(format!("invalid range '{}' for {}", range, colour)))?;
+            if from > to {
+                return Err(error(format!("empty range '{}' for {}", range, colour)));
+            }
+            low.push(CubeCount { count: from, colour: colour.to_string() });
+            high.push(CubeCount { count: to, colour: colour.to_string() });
+        }
+        Ok(BagRange { low: Bag { cubes: low }, high: Bag { cubes: high } })
+    }
+}
+
+/// Games that are possible for every bag in the range. Possibility only gets easier as a
+/// limit grows, so this is the set of games the lowest bag of the range allows.
+pub fn possible_for_every_bag<'a>(games: &'a [Game], range: &BagRange, strict: bool) -> Result<Vec<&'a Game>, UnknownColourError> {
+    let mut possible: Vec<&Game> = vec![];
+    for game in games {
+        range.low.check(game, strict)?;
+        if range.low.allows(game) {
+            possible.push(game);
+        }
+    }
+    Ok(possible)
+}
+
+/// Finds the bag over the configured colours with the smallest total number of cubes that
+/// makes at least `k` games possible, or `None` when there are fewer than `k` games.
+///
+/// Every colour but the last is tried at each limit some game actually needs; the last limit
+/// is then the k-th smallest requirement of the games still allowed. The search is exponential
+/// in the number of colours, which is fine for the handful a bag holds.
+pub fn smallest_bag_for(games: &[Game], colours: &Bag, k: usize, strict: bool) -> Result<Option<Bag>, UnknownColourError> {
+    for game in games {
+        colours.check(game, strict)?;
+    }
+    if k > games.len() {
+        return Ok(None);
+    }
+    if k == 0 {
+        let cubes = colours.cubes.iter().map(|cube| CubeCount { count: 0, colour: cube.colour.clone() }).collect();
+        return Ok(Some(Bag { cubes }));
+    }
+    let requirements: Vec<Vec<u32>> = games
+        .iter()
+        .map(|game| colours.cubes.iter().map(|cube| game.max_of(&cube.colour)).collect())
+        .collect();
+    let candidates: Vec<&Vec<u32>> = requirements.iter().collect();
+    let mut best: Option<(u64, Vec<u32>)> = None;
+    search_smallest_bag(&candidates, k, &mut vec![], &mut best);
+
+    Ok(best.map(|(_, limits)| Bag {
+        cubes: colours
+            .cubes
+            .iter()
+            .zip(limits)
+            .map(|(cube, count)| CubeCount { count, colour: cube.colour.clone() })
+            .collect(),
+    }))
+}
+
+fn search_smallest_bag(candidates: &[&Vec<u32>], k: usize, chosen: &mut Vec<u32>, best: &mut Option<(u64, Vec<u32>)>) {
+    let depth = chosen.len();
+    let total: u64 = chosen.iter().map(|&c| c as u64).sum();
+    if best.as_ref().is_some_and(|(best_total, _)| *best_total <= total) {
+        return;
+    }
+    let mut values: Vec<u32> = candidates.iter().map(|r| r[depth]).collect();
+    values.sort_unstable();
+    if depth + 1 == candidates[0].len() {
+        let limit = values[k - 1];
+        if best.as_ref().is_none_or(|(best_total, _)| total + (limit as u64) < *best_total) {
+            let mut limits = chosen.clone();
+            limits.push(limit);
+            *best = Some((total + limit as u64, limits));
+        }
+        return;
+    }
+    values.dedup();
+    for value in values {
+        let allowed: Vec<&Vec<u32>> = candidates.iter().filter(|r| r[depth] <= value).copied().collect();
+        if allowed.len() < k {
+            continue;
+        }
+        chosen.push(value);
+        search_smallest_bag(&allowed, k, chosen, best);
+        chosen.pop();
+    }
+}
+
+/// The tightest colour limit of a game: the colour with the least slack between the most
+/// cubes ever shown and the bag's limit. A negative slack means the game is impossible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingConstraint {
+    pub game: u32,
+    pub colour: String,
+    pub shown: u32,
+    pub limit: u32,
+    pub slack: i64,
+}
+
+/// The binding constraint of every game; ties go to the colour configured first.
+pub fn binding_constraints(games: &[Game], bag: &Bag, strict: bool) -> Result<Vec<BindingConstraint>, UnknownColourError> {
+    let mut constraints: Vec<BindingConstraint> = vec![];
+    for game in games {
+        bag.check(game, strict)?;
+        let binding = bag
+            .cubes
+            .iter()
+            .map(|cube| {
+                let shown = game.max_of(&cube.colour);
+                BindingConstraint {
+                    game: game.id,
+                    colour: cube.colour.clone(),
+                    shown,
+                    limit: cube.count,
+                    slack: cube.count as i64 - shown as i64,
+                }
+            })
+            .reduce(|tightest, next| if next.slack < tightest.slack { next } else { tightest });
+        constraints.extend(binding);
+    }
+    Ok(constraints)
+}
+
+pub fn solve_the_puzzle_2_2() {
     match file::read_file("inputs/day_2_2.txt") {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(contents) => {
-            let games: Vec<&str> = contents.lines().collect();
-            let mut result: i32 = 0;
-            for (i, game) in games.iter().enumerate() {
-                let trim_title: Vec<&str> = game.split(':').collect();
-                let subsets: Vec<&str> = trim_title[1].split(';').collect();
-                //println!("{}", trim_title[1]);
-                let mut possible: bool = true;
-                for (_set, subset) in subsets.iter().enumerate() {
-                    let cube_set: Vec<&str> = subset.split(',').collect();
-                    for (_time, cube) in cube_set.iter().enumerate() {
-                        let cube_set_1: Vec<&str> = cube.trim().split(' ').collect();
-                        let key: &str = cube_set_1[1];
-                        let value: i32 = cube_set_1[0].parse::<i32>().unwrap();
-                        //println!("set {} - time {}: {} {}", set, time, key, value);
-                        match key {
-                            "green" => {
-                                if value > GREEN {
-                                    possible = false;
-                                }
-                            }
-                            "red" => {
-                                if value > RED {
-                                    possible = false;
-                                }
-                            }
-                            "blue" => {
-                                if value > BLUE {
-                                    possible = false;
-                                }
-                            }
-                            _ => {
-                                // Ignore keys that are not "green", "red", or "blue"
-                            }
-                        }
-                    }
+        Ok(contents) => match parse_games(&contents) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(games) => match sum_of_possible_ids(&games, &Bag::default(), false) {
+                Err(error) => {
+                    println!("error {}", error)
                 }
-                if possible {
-                   // println!("possible {}", i + 1);
-                    result += (i + 1) as i32;
-                } else {
-                   // println!("impossible {}", i + 1);
+                Ok(result) => {
+                    println!("{}", result);
                 }
-            }
-            println!("{}", result);
-        }
+            },
+        },
     }
 }
 /*
@@ -156,41 +509,143 @@ pub fn solve_the_puzzle_2_3() {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(contents) => {
-            let games: Vec<&str> = contents.lines().collect();
-            let mut result: i32 = 0;
-            for (_i, game) in games.iter().enumerate() {
-                let trim_title: Vec<&str> = game.split(':').collect();
-                let subsets: Vec<&str> = trim_title[1].split(';').collect();
-                let mut required_conditions_map = HashMap::new();
-                //println!("{}", trim_title[1]);
-                for (_set, subset) in subsets.iter().enumerate() {
-                    let cube_set: Vec<&str> = subset.split(',').collect();
-                    for (_time, cube) in cube_set.iter().enumerate() {
-                        let cube_set_1: Vec<&str> = cube.trim().split(' ').collect();
-                        let key: &str = cube_set_1[1];
-                        let value: i32 = cube_set_1[0].parse::<i32>().unwrap();
-                        //println!("set {} - time {}: {} {}", set, time, key, value);
-                        match required_conditions_map.get(key) {
-                            Some(v) => {
-                                if value > *v {
-                                    required_conditions_map.insert(key, value);
-                                }
-                            }
-                            None => {
-                                required_conditions_map.insert(key, value);
-                            }
-                        }
-                    }
+        Ok(contents) => match parse_games(&contents) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(games) => match sum_of_powers(&games, &Bag::default(), false) {
+                Err(error) => {
+                    println!("error {}", error)
+                }
+                Ok(result) => {
+                    println!("{}", result);
                 }
-                let mut result_set = 1;
-                for (_k,v) in required_conditions_map.iter() {
-                    result_set *= v;
-                    //println!("{}: {} {} {}", i, k, v, result_set);
+            },
+        },
+    }
+}
+
+enum Query {
+    Sums,
+    Range(BagRange),
+    Smallest(usize),
+    Binding,
+}
+
+/// Runs the Cube Conundrum with a custom bag, optionally answering one feasibility query:
+/// `day_2 [range SPEC | smallest K | binding] [--input PATH] [--bag SPEC | --bag-file PATH] [--strict]`.
+/// Bags are `red=12,green=13,blue=14`; ranges are `red=10..14,green=13,...`.
+pub fn run(args: &[String]) {
+    let mut bag = Bag::default();
+    let mut strict = false;
+    let mut query = Query::Sums;
+    let parsed = parse::parse_args(args, "inputs/day_2_2.txt", |arg, args| {
+        match arg {
+            "--bag" => bag = Bag::parse(args.value(arg, "colour=count entries")?).map_err(|e| e.to_string())?,
+            "--bag-file" => {
+                let path = args.value(arg, "a path")?;
+                let spec = file::read_file(path).map_err(|e| format!("{}: {}", path, e))?;
+                bag = Bag::parse(&spec).map_err(|e| format!("{}: {}", path, e))?;
+            }
+            "--strict" => strict = true,
+            "range" => {
+                let spec = args.value(arg, "colour=min..max entries")?;
+                query = Query::Range(BagRange::parse(spec).map_err(|e| e.to_string())?);
+            }
+            "smallest" => query = Query::Smallest(args.parsed(arg, "a game count")?),
+            "binding" => query = Query::Binding,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    });
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            println!("error {}", error);
+            return;
+        }
+    };
+
+    match file::read_file(&input) {
+        Err(error) => {
+            println!("error {}", error)
+        }
+        Ok(contents) => match parse_games(&contents) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(games) => {
+                if let Err(error) = answer(&games, &bag, strict, &query) {
+                    println!("error {}", error)
                 }
-                result+=result_set;
             }
-            println!("{}", result);
+        },
+    }
+}
+
+fn answer(games: &[Game], bag: &Bag, strict: bool, query: &Query) -> Result<(), PowerError> {
+    match query {
+        Query::Sums => {
+            println!("Sum of possible game ids: {}", sum_of_possible_ids(games, bag, strict)?);
+            println!("Sum of powers: {}", sum_of_powers(games, bag, strict)?);
+        }
+        Query::Range(range) => {
+            let possible = possible_for_every_bag(games, range, strict)?;
+            let ids: Vec<String> = possible.iter().map(|game| game.id.to_string()).collect();
+            println!("{} games possible for every bag in range: {}", ids.len(), ids.join(", "));
+        }
+        Query::Smallest(k) => match smallest_bag_for(games, bag, *k, strict)? {
+            None => println!("Only {} games, cannot make {} possible", games.len(), k),
+            Some(smallest) => {
+                let total: u64 = smallest.cubes.iter().map(|cube| cube.count as u64).sum();
+                let cubes: Vec<String> = smallest.cubes.iter().map(|cube| format!("{}={}", cube.colour, cube.count)).collect();
+                println!("Smallest bag for {} games: {} cubes ({})", k, total, cubes.join(","));
+            }
+        },
+        Query::Binding => {
+            for constraint in binding_constraints(games, bag, strict)? {
+                println!(
+                    "Game {}: {} shown {} of {} (slack {})",
+                    constraint.game, constraint.colour, constraint.shown, constraint.limit, constraint.slack
+                );
+            }
         }
     }
-}
\ No newline at end of file
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum_of_powers_matches_the_example() {
+        let games = parse_games(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n\
+             Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n\
+             Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red\n\
+             Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red\n\
+             Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green\n",
+        )
+        .unwrap();
+        assert_eq!(sum_of_powers(&games, &Bag::default(), false), Ok(2286));
+    }
+
+    #[test]
+    fn powers_past_u64_are_reported() {
+        let games = parse_games("Game 7: 4294967295 red, 4294967295 green, 2 blue\n").unwrap();
+        assert_eq!(
+            sum_of_powers(&games, &Bag::default(), false),
+            Err(PowerError::Overflow("power of game 7".to_string()))
+        );
+
+        let games = parse_games(
+            "Game 1: 4294967295 red, 4294967295 green, 1 blue\n\
+             Game 2: 4294967295 red, 4294967295 green, 1 blue\n",
+        )
+        .unwrap();
+        assert_eq!(
+            sum_of_powers(&games, &Bag::default(), false),
+            Err(PowerError::Overflow("sum of powers".to_string()))
+        );
+    }
+}
@@ -1,7 +1,7 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
+use crate::utils::grid::{Grid, Point};
+use crate::utils::parse::{self, Args, ParseError};
 
-use std::collections::HashMap;
 use std::fmt;
 
 /*
@@ -31,92 +31,194 @@ In this schematic, two numbers are not part numbers because they are not adjacen
 Every other number is adjacent to a symbol and so is a part number; their sum is 4361.
 Of course, the actual engine schematic is much larger. What is the sum of all of the part numbers in the engine schematic?
 */
-#[derive(Debug)]
-struct NumLoc {
-    row: usize,
-    col: usize,
+/// A run of digits on one row of the schematic, covering columns `col_start..col_end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberSpan {
+    pub row: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+    pub value: u32,
 }
 
-impl fmt::Display for NumLoc {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({},{})", self.row, self.col)
+impl NumberSpan {
+    /// Whether `point` lies in the rectangle one cell around the span (or on the span itself).
+    pub fn is_adjacent(&self, point: Point) -> bool {
+        point.row + 1 >= self.row
+            && point.row <= self.row + 1
+            && point.col + 1 >= self.col_start
+            && point.col <= self.col_end
+    }
+
+    /// Every in-bounds cell of the rectangle one cell around the span, the span included.
+    pub fn surrounding(&self, rows: usize, cols: usize) -> impl Iterator<Item = Point> {
+        let (col_start, col_end) = (self.col_start.saturating_sub(1), (self.col_end + 1).min(cols));
+        (self.row.saturating_sub(1)..(self.row + 2).min(rows))
+            .flat_map(move |row| (col_start..col_end).map(move |col| Point::new(row, col)))
     }
 }
 
-pub fn solve_the_puzzle_3_1() {
-    match file::read_file("inputs/day_3_1.txt") {
-        Err(error) => {
-            println!("error {}", error)
+pub fn is_symbol(ch: char) -> bool {
+    !ch.is_ascii_digit() && ch != '.'
+}
+
+/// Which symbols a query looks at. `Only` never matches digits or `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolSet {
+    Any,
+    Only(Vec<char>),
+}
+
+impl SymbolSet {
+    /// `any` selects every symbol, anything else is the list of symbol characters, e.g. `*#`.
+    pub fn parse(spec: &str) -> SymbolSet {
+        if spec == "any" {
+            SymbolSet::Any
+        } else {
+            SymbolSet::Only(spec.chars().collect())
         }
-        Ok(schematic) => {
-            let mut row_count = 0;
-            let mut col_count = 0;
-            for line in schematic.lines() {
-                if row_count == 0 {
-                    let cols: Vec<char> = line.chars().clone().into_iter().collect();
-                    col_count = cols.len();
-                }
-                row_count += 1;
-            }
-            let mut sum = 0;
-            let mut symbols = vec![vec!['.'; col_count]; row_count];
-            let mut number_parts: HashMap<usize, i32> = HashMap::new();
-            let mut symbol_adjacent: Vec<NumLoc> = Vec::new();
-
-            for (row, line) in schematic.lines().enumerate() {
-                for (col, ch) in line.chars().enumerate() {
-                    if !ch.is_digit(10) && ch != '.' {
-                        for i in row.saturating_sub(1)..=row + 1 {
-                            for j in col.saturating_sub(1)..=col + 1 {
-                                if i < row || i > row || j < col || j > col {
-                                    symbol_adjacent.push(NumLoc { row: i, col: j });
-                                }
-                            }
-                        }
-                        //println!("{} ({},{}) -> {:?}", ch, row, col, symbol_adjacent);
-                    }
-                    symbols[row][col] = ch;
-                }
+    }
+
+    pub fn contains(&self, ch: char) -> bool {
+        is_symbol(ch)
+            && match self {
+                SymbolSet::Any => true,
+                SymbolSet::Only(set) => set.contains(&ch),
             }
-            for adj in symbol_adjacent {
-                let c = symbols[adj.row][adj.col];
-                if c.is_digit(10) {
-                    let mut number = format!("{}", c);
-                    let mut start = adj.col as usize;
-                    let mut left = start - 1;
-                    let mut right = start + 1;
-                    while left >= 0 {
-                        //println!("{}", left);
-                        let l = symbols[adj.row][left];
-                        if l.is_digit(10) {
-                            number = format!("{}{}", l, number);
-                            start = left;
-                            if left == 0 {
-                                break;
-                            }
-                            left -= 1;
-                        } else { break; }
-                    }
-                    while right < col_count {
-                        let r = symbols[adj.row][right];
-                        if r.is_digit(10) {
-                            number = format!("{}{}", number, r);
-                            right += 1;
-                        } else { break; }
+    }
+}
+
+impl fmt::Display for SymbolSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolSet::Any => write!(f, "any symbol"),
+            SymbolSet::Only(set) => write!(f, "{}", set.iter().collect::<String>()),
+        }
+    }
+}
+
+/// The numbers around one symbol. The product is 1 when no number touches the symbol and
+/// saturates rather than overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolAggregate {
+    pub point: Point,
+    pub symbol: char,
+    pub count: usize,
+    pub sum: u64,
+    pub product: u64,
+}
+
+/// The engine schematic with every number tokenised once, in reading order.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub numbers: Vec<NumberSpan>,
+}
+
+impl Schematic {
+    pub fn parse(text: &str) -> Result<Schematic, ParseError> {
+        let grid = Grid::parse(text)?;
+        let mut numbers: Vec<NumberSpan> = vec![];
+        for (row, cells) in grid.iter_rows().enumerate() {
+            let mut current: Option<NumberSpan> = None;
+            for (col, ch) in cells.iter().enumerate() {
+                match (ch.to_digit(10), current.as_mut()) {
+                    (Some(digit), Some(span)) => {
+                        span.value = span.value.checked_mul(10).and_then(|v| v.checked_add(digit)).ok_or(ParseError {
+                            line: row + 1,
+                            column: span.col_start + 1,
+                            message: "number is too large".to_string(),
+                        })?;
+                        span.col_end = col + 1;
                     }
-                    let num = number.parse::<i32>().unwrap();
-                    let key = start + col_count * adj.row;//row * num_columns + column
-                    if !number_parts.contains_key(&key) {
-                        number_parts.insert(key, num);
+                    (Some(digit), None) => {
+                        current = Some(NumberSpan { row, col_start: col, col_end: col + 1, value: digit });
                     }
+                    (None, _) => numbers.extend(current.take()),
                 }
             }
-            for (_k, v) in number_parts.iter() {
-                sum += v;
+            numbers.extend(current);
+        }
+        Ok(Schematic { grid, numbers })
+    }
+
+    /// Whether any symbol touches the span, even diagonally.
+    pub fn is_part_number(&self, span: &NumberSpan) -> bool {
+        span.surrounding(self.grid.rows(), self.grid.cols()).any(|p| is_symbol(self.grid[p]))
+    }
+
+    pub fn part_numbers(&self) -> impl Iterator<Item = &NumberSpan> {
+        self.numbers.iter().filter(|span| self.is_part_number(span))
+    }
+
+    /// Numbers touching `point`. Spans are sorted by row, so only the three rows around the
+    /// point are scanned.
+    pub fn numbers_adjacent_to(&self, point: Point) -> impl Iterator<Item = &NumberSpan> {
+        let start = self.numbers.partition_point(|span| span.row + 1 < point.row);
+        let end = self.numbers.partition_point(|span| span.row <= point.row + 1);
+        self.numbers[start..end].iter().filter(move |span| span.is_adjacent(point))
+    }
+
+    /// Every symbol of the set with its position, in reading order.
+    pub fn symbols(&self, set: &SymbolSet) -> Vec<(Point, char)> {
+        self.grid.iter().filter(|(_, &ch)| set.contains(ch)).map(|(point, &ch)| (point, ch)).collect()
+    }
+
+    /// Numbers touching at least one symbol of the set, each reported once.
+    pub fn numbers_adjacent_to_symbols(&self, set: &SymbolSet) -> Vec<&NumberSpan> {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        self.numbers
+            .iter()
+            .filter(|span| span.surrounding(rows, cols).any(|p| set.contains(self.grid[p])))
+            .collect()
+    }
+
+    pub fn sum_adjacent_to(&self, set: &SymbolSet) -> u64 {
+        self.numbers_adjacent_to_symbols(set).iter().map(|span| span.value as u64).sum()
+    }
+
+    /// Numbers touching no symbol at all, like 114 and 58 in the example.
+    pub fn isolated_numbers(&self) -> impl Iterator<Item = &NumberSpan> {
+        self.numbers.iter().filter(|span| !self.is_part_number(span))
+    }
+
+    /// Count, sum and product of the numbers around every symbol of the set.
+    pub fn aggregates(&self, set: &SymbolSet) -> Vec<SymbolAggregate> {
+        self.symbols(set).into_iter().map(|(point, symbol)| {
+            let mut aggregate = SymbolAggregate { point, symbol, count: 0, sum: 0, product: 1 };
+            for span in self.numbers_adjacent_to(point) {
+                aggregate.count += 1;
+                aggregate.sum += span.value as u64;
+                aggregate.product = aggregate.product.saturating_mul(span.value as u64);
             }
-            //println!("{:?}", number_parts);
-            println!("The sum of all part numbers in the engine schematic is {}", sum);
+            aggregate
+        }).collect()
+    }
+
+    /// Symbols of the set touching exactly `k` numbers.
+    pub fn symbols_with_exactly(&self, set: &SymbolSet, k: usize) -> Vec<SymbolAggregate> {
+        self.aggregates(set).into_iter().filter(|aggregate| aggregate.count == k).collect()
+    }
+
+    /// Product of the two part numbers of every `*` touching exactly two numbers.
+    pub fn gear_ratios(&self) -> impl Iterator<Item = u64> {
+        self.gears().into_iter().map(|gear| gear.ratio())
+    }
+}
+
+pub fn solve_the_puzzle_3_1() {
+    match file::read_file("inputs/day_3_1.txt") {
+        Err(error) => {
+            println!("error {}", error)
         }
+        Ok(input) => match Schematic::parse(&input) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(schematic) => {
+                let sum = schematic.sum_adjacent_to(&SymbolSet::Any);
+                println!("The sum of all part numbers in the engine schematic is {}", sum);
+            }
+        },
     }
 }
 
@@ -149,87 +251,365 @@ pub fn solve_the_puzzle_3_2() {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(schematic) => {
-            let mut row_count = 0;
-            let mut col_count = 0;
-            for line in schematic.lines() {
-                if row_count == 0 {
-                    let cols: Vec<char> = line.chars().clone().into_iter().collect();
-                    col_count = cols.len();
-                }
-                row_count += 1;
-            }
-            let mut sum = 0;
-            let mut symbols = vec![vec!['.'; col_count]; row_count];
-            let mut gear_map: HashMap<usize, Vec<NumLoc>> = HashMap::new();
-
-            for (row, line) in schematic.lines().enumerate() {
-                for (col, ch) in line.chars().enumerate() {
-                    if ch == '*' {
-                        let mut gear_loc: Vec<NumLoc> = Vec::new();
-                        for i in row.saturating_sub(1)..=row + 1 {
-                            for j in col.saturating_sub(1)..=col + 1 {
-                                if i < row || i > row || j < col || j > col {
-                                    gear_loc.push(NumLoc { row: i, col: j });
-                                }
-                            }
-                        }
-                        let key = col + col_count * row;
-                        gear_map.insert(key, gear_loc);
-                    }
-                    symbols[row][col] = ch;
+        Ok(input) => match Schematic::parse(&input) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(schematic) => {
+                let sum: u64 = schematic.gear_ratios().sum();
+                println!("The sum of all part numbers in the engine schematic is {}", sum);
+            }
+        },
+    }
+}
+
+/// A `*` touching exactly two numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gear {
+    pub point: Point,
+    pub parts: [NumberSpan; 2],
+}
+
+impl Gear {
+    pub fn ratio(&self) -> u64 {
+        self.parts[0].value as u64 * self.parts[1].value as u64
+    }
+}
+
+/// What a schematic cell is, for rendering. Numbers touching a gear are `GearPart`s even
+/// though they are also part numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    Blank,
+    Symbol,
+    Gear,
+    PartNumber,
+    GearPart,
+    NonPartNumber,
+}
+
+impl CellKind {
+    /// ANSI SGR parameters for the terminal renderer.
+    fn ansi(&self) -> &'static str {
+        match self {
+            CellKind::Blank => "2",
+            CellKind::Symbol => "35",
+            CellKind::Gear => "1;33",
+            CellKind::PartNumber => "32",
+            CellKind::GearPart => "1;36",
+            CellKind::NonPartNumber => "31",
+        }
+    }
+
+    /// CSS class for the HTML renderer.
+    fn class(&self) -> &'static str {
+        match self {
+            CellKind::Blank => "blank",
+            CellKind::Symbol => "symbol",
+            CellKind::Gear => "gear",
+            CellKind::PartNumber => "part",
+            CellKind::GearPart => "gear-part",
+            CellKind::NonPartNumber => "non-part",
+        }
+    }
+}
+
+const LEGEND: [(CellKind, &str); 5] = [
+    (CellKind::PartNumber, "part number"),
+    (CellKind::NonPartNumber, "not a part number"),
+    (CellKind::Gear, "gear"),
+    (CellKind::GearPart, "gear part number"),
+    (CellKind::Symbol, "other symbol"),
+];
+
+impl Schematic {
+    pub fn gears(&self) -> Vec<Gear> {
+        self.symbols(&SymbolSet::Only(vec!['*']))
+            .into_iter()
+            .filter_map(|(point, _)| {
+                let mut adjacent = self.numbers_adjacent_to(point);
+                match (adjacent.next(), adjacent.next(), adjacent.next()) {
+                    (Some(&a), Some(&b), None) => Some(Gear { point, parts: [a, b] }),
+                    _ => None,
                 }
+            })
+            .collect()
+    }
+
+    pub fn classify(&self) -> Grid<CellKind> {
+        let mut kinds = Grid::new(self.grid.rows(), self.grid.cols(), CellKind::Blank);
+        for (point, &ch) in self.grid.iter() {
+            if is_symbol(ch) {
+                kinds[point] = CellKind::Symbol;
             }
-            //println!("{:?}", gear_map);
-            let mut gear_loc_map: HashMap<usize, HashMap<usize, i32>> = HashMap::new();
-            for (k, gears_loc) in gear_map.iter() {
-                let mut loc_map: HashMap<usize, i32> = HashMap::new();
-                for num_loc in gears_loc {
-                    let c = symbols[num_loc.row][num_loc.col];
-                    if c.is_digit(10) {
-                        let mut number = format!("{}", c);
-                        let mut start = num_loc.col as usize;
-                        let mut left = start - 1;
-                        let mut right = start + 1;
-                        while left >= 0 {
-                            //println!("{}", left);
-                            let l = symbols[num_loc.row][left];
-                            if l.is_digit(10) {
-                                number = format!("{}{}", l, number);
-                                start = left;
-                                if left == 0 {
-                                    break;
-                                }
-                                left -= 1;
-                            } else { break; }
-                        }
-                        while right < col_count {
-                            let r = symbols[num_loc.row][right];
-                            if r.is_digit(10) {
-                                number = format!("{}{}", number, r);
-                                right += 1;
-                            } else { break; }
-                        }
-                        let num = number.parse::<i32>().unwrap();
-                        let key = start + col_count * num_loc.row;//row * num_columns + column
-                        loc_map.insert(key, num);
-                    }
+        }
+        for span in &self.numbers {
+            let kind = if self.is_part_number(span) { CellKind::PartNumber } else { CellKind::NonPartNumber };
+            for col in span.col_start..span.col_end {
+                kinds[Point::new(span.row, col)] = kind;
+            }
+        }
+        for gear in self.gears() {
+            kinds[gear.point] = CellKind::Gear;
+            for span in gear.parts {
+                for col in span.col_start..span.col_end {
+                    kinds[Point::new(span.row, col)] = CellKind::GearPart;
                 }
-                gear_loc_map.insert(*k, loc_map);
-            }
-            //println!("{:?}",gear_loc_map);
-            for (_k, v) in gear_loc_map.iter() {
-                if v.len() == 2 {
-                    let mut mul = 1;
-                    for (_k, n) in v.iter() {
-                        mul *= n;
-                    }
-                    sum += mul;
-                    //println!("{:?},{}", v, sum);
+            }
+        }
+        kinds
+    }
+
+    /// Column numbers above the map: the full number starting at every tenth column, so
+    /// column 100 reads `100` rather than repeating column 0, then the units digit of each.
+    fn column_ruler(&self, margin: usize) -> [String; 2] {
+        let labels: String = (0..self.grid.cols()).step_by(10).map(|col| format!("{:<10}", col)).collect();
+        let units: String = (0..self.grid.cols()).map(|col| char::from_digit((col % 10) as u32, 10).unwrap()).collect();
+        [format!("{:margin$}{}", "", labels.trim_end()), format!("{:margin$}{}", "", units)]
+    }
+
+    fn margin(&self, rulers: bool) -> usize {
+        if rulers {
+            self.grid.rows().saturating_sub(1).to_string().len() + 1
+        } else {
+            0
+        }
+    }
+
+    /// The schematic coloured with ANSI escapes, followed by a legend and every gear ratio.
+    pub fn render_ansi(&self, rulers: bool) -> String {
+        let kinds = self.classify();
+        let margin = self.margin(rulers);
+        let mut out = String::new();
+        if rulers {
+            for line in self.column_ruler(margin) {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        for (row, cells) in self.grid.iter_rows().enumerate() {
+            if rulers {
+                out.push_str(&format!("{:>width$} ", row, width = margin - 1));
+            }
+            for (kind, _, text) in runs(&kinds, row, cells) {
+                out.push_str(&format!("\x1b[{}m{}\x1b[0m", kind.ansi(), text));
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+        for (kind, label) in LEGEND {
+            out.push_str(&format!("\x1b[{}m{}\x1b[0m ", kind.ansi(), label));
+        }
+        out.push('\n');
+        for gear in self.gears() {
+            out.push_str(&format!(
+                "gear at {}: \x1b[{p}m{}\x1b[0m * \x1b[{p}m{}\x1b[0m = {}\n",
+                gear.point,
+                gear.parts[0].value,
+                gear.parts[1].value,
+                gear.ratio(),
+                p = CellKind::GearPart.ansi()
+            ));
+        }
+        out
+    }
+
+    /// A standalone HTML page with the coloured schematic; hovering a gear shows its ratio.
+    pub fn render_html(&self, rulers: bool) -> String {
+        let kinds = self.classify();
+        let margin = self.margin(rulers);
+        let gears = self.gears();
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Engine schematic</title>\n<style>\n\
+             body { background: #10101a; color: #cccccc; font-family: monospace; }\n\
+             pre { line-height: 1.2; }\n\
+             .blank { color: #444455; }\n.symbol { color: #d070d0; }\n.gear { color: #ffd000; font-weight: bold; }\n\
+             .part { color: #40c040; }\n.gear-part { color: #40e0e0; font-weight: bold; }\n.non-part { color: #ff5050; }\n\
+             .ruler { color: #777788; }\n\
+             </style>\n</head>\n<body>\n<pre>\n",
+        );
+        if rulers {
+            for line in self.column_ruler(margin) {
+                out.push_str(&format!("<span class=\"ruler\">{}</span>\n", line));
+            }
+        }
+        for (row, cells) in self.grid.iter_rows().enumerate() {
+            if rulers {
+                out.push_str(&format!("<span class=\"ruler\">{:>width$} </span>", row, width = margin - 1));
+            }
+            for (kind, col, text) in runs(&kinds, row, cells) {
+                let text = escape_html(&text);
+                match gears.iter().find(|gear| gear.point == Point::new(row, col)) {
+                    Some(gear) => out.push_str(&format!(
+                        "<span class=\"{}\" title=\"{} * {} = {}\">{}</span>",
+                        kind.class(),
+                        gear.parts[0].value,
+                        gear.parts[1].value,
+                        gear.ratio(),
+                        text
+                    )),
+                    None => out.push_str(&format!("<span class=\"{}\">{}</span>", kind.class(), text)),
                 }
             }
-            //println!("{:?}", gear_map);
-            println!("The sum of all part numbers in the engine schematic is {}", sum);
+            out.push('\n');
+        }
+        out.push_str("</pre>\n<p>");
+        for (kind, label) in LEGEND {
+            out.push_str(&format!("<span class=\"{}\">{}</span> ", kind.class(), label));
         }
+        out.push_str("</p>\n<ul>\n");
+        for gear in &gears {
+            out.push_str(&format!(
+                "<li><span class=\"gear\">gear</span> at {}: <span class=\"gear-part\">{}</span> * <span class=\"gear-part\">{}</span> = {}</li>\n",
+                gear.point,
+                gear.parts[0].value,
+                gear.parts[1].value,
+                gear.ratio()
+            ));
+        }
+        out.push_str("</ul>\n</body>\n</html>\n");
+        out
     }
-}
\ No newline at end of file
+}
+
+/// Splits a row into runs of cells of the same kind, as `(kind, first column, text)`, so each
+/// run needs a single colour change. Gears always get a run of their own.
+fn runs(kinds: &Grid<CellKind>, row: usize, cells: &[char]) -> Vec<(CellKind, usize, String)> {
+    let mut runs: Vec<(CellKind, usize, String)> = vec![];
+    for (col, &ch) in cells.iter().enumerate() {
+        let kind = kinds[Point::new(row, col)];
+        match runs.last_mut() {
+            Some((last, _, text)) if *last == kind && kind != CellKind::Gear => text.push(ch),
+            _ => runs.push((kind, col, ch.to_string())),
+        }
+    }
+    runs
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+enum Query {
+    Parts,
+    Sum(SymbolSet),
+    Exactly(usize, SymbolSet),
+    Isolated,
+    Aggregate(SymbolSet),
+    Render { html: Option<String>, rulers: bool },
+}
+
+/// Queries the engine schematic:
+/// `day_3 [sum SYMBOLS | exactly K [SYMBOLS] | isolated | aggregate [SYMBOLS] | render] [--input PATH]`.
+/// `render` prints the coloured schematic, or writes an HTML page with `--html PATH`; `--rulers`
+/// adds row and column numbers.
+/// SYMBOLS is a list of symbol characters such as `*#`, or `any` (the default).
+pub fn run(args: &[String]) {
+    let mut query = Query::Parts;
+    let parsed = parse::parse_args(args, "inputs/day_3_1.txt", |arg, args| {
+        match arg {
+            "sum" => query = Query::Sum(symbol_set(args)),
+            "exactly" => {
+                let k = args.parsed(arg, "a number count")?;
+                query = Query::Exactly(k, symbol_set(args));
+            }
+            "isolated" => query = Query::Isolated,
+            "aggregate" => query = Query::Aggregate(symbol_set(args)),
+            "render" => query = Query::Render { html: None, rulers: false },
+            "--html" => match &mut query {
+                Query::Render { html, .. } => *html = Some(args.value(arg, "a path")?.to_string()),
+                _ => return Err("--html only applies to render".to_string()),
+            },
+            "--rulers" => match &mut query {
+                Query::Render { rulers, .. } => *rulers = true,
+                _ => return Err("--rulers only applies to render".to_string()),
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    });
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            println!("error {}", error);
+            return;
+        }
+    };
+
+    match file::read_file(&input) {
+        Err(error) => {
+            println!("error {}", error)
+        }
+        Ok(text) => match Schematic::parse(&text) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(schematic) => answer(&schematic, &query),
+        },
+    }
+}
+
+/// Symbol sets are optional, so only the next argument is taken, and only if it is not an option.
+fn symbol_set(args: &mut Args) -> SymbolSet {
+    args.next_value().map_or(SymbolSet::Any, SymbolSet::parse)
+}
+
+fn answer(schematic: &Schematic, query: &Query) {
+    match query {
+        Query::Parts => {
+            println!("Sum of part numbers: {}", schematic.sum_adjacent_to(&SymbolSet::Any));
+            println!("Sum of gear ratios: {}", schematic.gear_ratios().sum::<u64>());
+        }
+        Query::Sum(set) => {
+            println!("Sum of numbers next to {}: {}", set, schematic.sum_adjacent_to(set));
+        }
+        Query::Exactly(_, set) | Query::Aggregate(set) => {
+            let aggregates = match query {
+                Query::Exactly(k, _) => schematic.symbols_with_exactly(set, *k),
+                _ => schematic.aggregates(set),
+            };
+            for aggregate in aggregates {
+                println!(
+                    "{} at {}: {} numbers, sum {}, product {}",
+                    aggregate.symbol, aggregate.point, aggregate.count, aggregate.sum, aggregate.product
+                );
+            }
+        }
+        Query::Isolated => {
+            for span in schematic.isolated_numbers() {
+                println!("{} at {}", span.value, Point::new(span.row, span.col_start));
+            }
+        }
+        Query::Render { html: None, rulers } => {
+            print!("{}", schematic.render_ansi(*rulers));
+        }
+        Query::Render { html: Some(path), rulers } => match file::write_file(path, &schematic.render_html(*rulers)) {
+            Err(error) => println!("error {}: {}", path, error),
+            Ok(()) => println!("Wrote {}", path),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_numbers_are_summed_without_overflow() {
+        let schematic = Schematic::parse("4000000000*4000000000\n").unwrap();
+        assert_eq!(schematic.part_numbers().count(), 2);
+        assert_eq!(schematic.sum_adjacent_to(&SymbolSet::Any), 8_000_000_000);
+    }
+
+    #[test]
+    fn column_ruler_labels_every_tenth_column_in_full() {
+        let schematic = Schematic::parse(&format!("{}\n", ".".repeat(112))).unwrap();
+        let [labels, units] = schematic.column_ruler(2);
+        for col in (0..112).step_by(10) {
+            assert!(labels[2 + col..].starts_with(&format!("{:<3}", col)), "column {}", col);
+        }
+        assert!(labels.ends_with("110"));
+        assert_eq!(&units[2..14], "012345678901");
+        assert_eq!(units.len(), 2 + 112);
+    }
+}
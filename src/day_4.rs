@@ -1,7 +1,6 @@
-#[path = "utils/file.rs"]
-mod file;
-
-use std::collections::HashMap;
+use crate::utils::file;
+use crate::utils::number;
+use crate::utils::parse::{self, Cursor, ParseError};
 
 /*
 --- Day 4: Scratchcards ---
@@ -38,46 +37,225 @@ So, in this example, the Elf's pile of scratchcards is worth 13 points.
 Take a seat in the large pile of colorful cards. How many points are they worth in total?
 */
 
+/// A set of small non-negative numbers stored as a bitset, one bit per number.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberSet {
+    words: Vec<u64>,
+}
+
+impl NumberSet {
+    /// Numbers are kept below this, which bounds the bitset at 8 KiB; holding `n` takes
+    /// `n / 64 + 1` words.
+    pub const LIMIT: u32 = 1 << 16;
+
+    pub fn new() -> NumberSet {
+        NumberSet { words: vec![] }
+    }
+
+    /// Adds `n`, returning `false` if it was already present.
+    pub fn insert(&mut self, n: u32) -> bool {
+        let (word, bit) = (n as usize / 64, n % 64);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let present = self.words[word] & (1 << bit) != 0;
+        self.words[word] |= 1 << bit;
+        !present
+    }
+
+    pub fn contains(&self, n: u32) -> bool {
+        self.words.get(n as usize / 64).is_some_and(|word| word & (1 << (n % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Number of values present in both sets.
+    pub fn intersection_len(&self, other: &NumberSet) -> usize {
+        self.words.iter().zip(&other.words).map(|(a, b)| (a & b).count_ones() as usize).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            (0..64).filter(move |bit| word & (1 << bit) != 0).map(move |bit| (index * 64) as u32 + bit)
+        })
+    }
+}
+
+impl FromIterator<u32> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> NumberSet {
+        let mut set = NumberSet::new();
+        for n in iter {
+            set.insert(n);
+        }
+        set
+    }
+}
+
+/// What to do with a number listed more than once among the numbers you have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Refuse the card.
+    Reject,
+    /// Count the number once, however often it is listed.
+    Once,
+    /// Count every listing as a separate match.
+    Each,
+}
+
+impl Duplicates {
+    pub fn parse(name: &str) -> Option<Duplicates> {
+        match name {
+            "reject" => Some(Duplicates::Reject),
+            "once" => Some(Duplicates::Once),
+            "each" => Some(Duplicates::Each),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scratchcard {
+    pub id: usize,
+    pub winning: NumberSet,
+    pub have: NumberSet,
+    /// Extra listings of numbers already in `have`; only kept under `Duplicates::Each`.
+    pub repeats: Vec<u32>,
+}
+
+impl Scratchcard {
+    /// Parses a `Card N: winning numbers | numbers you have` line. Winning numbers must be
+    /// distinct; repeated numbers you have are handled according to `duplicates`.
+    pub fn parse(text: &str, line: usize, duplicates: Duplicates) -> Result<Scratchcard, ParseError> {
+        let mut cursor = Cursor::new(text, line);
+        cursor.skip_spaces();
+        cursor.expect("Card")?;
+        cursor.skip_spaces();
+        let start = cursor.pos();
+        let id: usize = cursor.number()?;
+        if id == 0 {
+            cursor.seek(start);
+            return Err(cursor.error("card ids start at 1"));
+        }
+        cursor.expect(":")?;
+        let mut winning = NumberSet::new();
+        for (pos, n) in parse_numbers(&mut cursor)? {
+            if !winning.insert(n) {
+                cursor.seek(pos);
+                return Err(cursor.error(format!("card {}: winning number {} is listed twice", id, n)));
+            }
+        }
+        cursor.expect("|")?;
+        let mut have = NumberSet::new();
+        let mut repeats: Vec<u32> = vec![];
+        for (pos, n) in parse_numbers(&mut cursor)? {
+            if !have.insert(n) {
+                match duplicates {
+                    Duplicates::Reject => {
+                        cursor.seek(pos);
+                        return Err(cursor.error(format!("card {}: number {} is listed twice", id, n)));
+                    }
+                    Duplicates::Once => {}
+                    Duplicates::Each => repeats.push(n),
+                }
+            }
+        }
+        if !cursor.is_at_end() {
+            return Err(cursor.error(format!("card {}: expected a number or end of line", id)));
+        }
+        Ok(Scratchcard { id, winning, have, repeats })
+    }
+
+    /// How many of the numbers you have are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.winning.intersection_len(&self.have) + self.repeats.iter().filter(|&&n| self.winning.contains(n)).count()
+    }
+
+    /// 1 point for the first match, doubled for every match after it, or `None` past
+    /// `usize`, at 65 matches on 64-bit targets.
+    pub fn points(&self) -> Option<usize> {
+        match self.matches() {
+            0 => Some(0),
+            matches => u32::try_from(matches - 1).ok().and_then(|shift| 1usize.checked_shl(shift)),
+        }
+    }
+}
+
+/// Points of the whole pile, or `None` if a card's points or the total overflow `usize`.
+pub fn total_points(cards: &[Scratchcard]) -> Option<usize> {
+    number::checked_sum(cards.iter().map(Scratchcard::points).collect::<Option<Vec<usize>>>()?)
+}
+
+/// Whitespace-separated numbers with the byte offset each one starts at. Numbers must stay
+/// below `NumberSet::LIMIT`.
+fn parse_numbers(cursor: &mut Cursor) -> Result<Vec<(usize, u32)>, ParseError> {
+    let mut numbers: Vec<(usize, u32)> = vec![];
+    cursor.skip_spaces();
+    while cursor.peek().is_some_and(|c| c.is_ascii_digit()) {
+        let start = cursor.pos();
+        let n: u32 = cursor.number()?;
+        if n >= NumberSet::LIMIT {
+            cursor.seek(start);
+            return Err(cursor.error(format!("number {} is too large, numbers must be below {}", n, NumberSet::LIMIT)));
+        }
+        numbers.push((start, n));
+        cursor.skip_spaces();
+    }
+    Ok(numbers)
+}
+
+/// Parses every card and orders them by id. Cards may be listed in any order, but the ids
+/// must be exactly 1 to N, since a card wins copies of the cards numbered after it.
+pub fn parse_scratchcards(input: &str, duplicates: Duplicates) -> Result<Vec<Scratchcard>, ParseError> {
+    let mut cards: Vec<(usize, Scratchcard)> = vec![];
+    for (index, text) in input.lines().enumerate() {
+        if !text.trim().is_empty() {
+            cards.push((index + 1, Scratchcard::parse(text, index + 1, duplicates)?));
+        }
+    }
+    cards.sort_by_key(|(_, card)| card.id);
+    if let Some((line, card)) = cards.first().filter(|(_, card)| card.id != 1) {
+        return Err(ParseError { line: *line, column: 1, message: format!("card {}: card 1 is missing", card.id) });
+    }
+    for pair in cards.windows(2) {
+        let ((first_line, first), (line, card)) = (&pair[0], &pair[1]);
+        if card.id == first.id {
+            return Err(ParseError {
+                line: *line,
+                column: 1,
+                message: format!("card {} is listed twice, first on line {}", card.id, first_line),
+            });
+        }
+        if card.id != first.id + 1 {
+            return Err(ParseError {
+                line: *line,
+                column: 1,
+                message: format!("card {}: card {} is missing", card.id, first.id + 1),
+            });
+        }
+    }
+    Ok(cards.into_iter().map(|(_, card)| card).collect())
+}
+
 pub fn solve_the_puzzle_4_1() {
     match file::read_file("inputs/day_4_1.txt") {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(schematic) => {
-            let mut sum = 0;
-            for line in schematic.lines() {
-                let mut numbers: Vec<i32> = vec![];
-                let mut winning_map: HashMap<i32, i32> = HashMap::new();
-                let parts: Vec<&str> = line.split(':').collect();
-                let number_parts: Vec<&str> = parts[1].split('|').collect();
-                let number_vec: Vec<&str> = number_parts[1].split_whitespace().collect();
-                for np in number_vec {
-                    let value: i32 = np.parse::<i32>().unwrap();
-                    numbers.push(value);
-                }
-                let winning_vec: Vec<&str> = number_parts[0].split_whitespace().collect();
-                for wn in winning_vec {
-                    let value: i32 = wn.parse::<i32>().unwrap();
-                    winning_map.insert(value, 0);
-                }
-                for num in numbers {
-                    if winning_map.contains_key(&num) {
-                        winning_map.insert(num, 1);
-                    }
-                }
-                let mut com = 0;
-                for (_k, v) in winning_map.iter() {
-                    if *v > 0 {
-                        com += 1;
-                    }
-                }
-                if com > 0 {
-                    //println!("{}", com);
-                    sum += 2_i32.pow(com - 1);
-                }
+        Ok(input) => match parse_scratchcards(&input, Duplicates::Reject) {
+            Err(error) => {
+                println!("error {}", error)
             }
-            println!("{}", sum);
-        }
+            Ok(cards) => match total_points(&cards) {
+                Some(sum) => println!("{}", sum),
+                None => println!("error points overflow usize"),
+            },
+        },
     }
 }
 
@@ -108,10 +286,27 @@ Once all of the originals and copies have been processed, you destination up wit
 In total, this example pile of scratchcards causes you to ultimately have 30 scratchcards!
 Process all of the original and copied scratchcards until no more scratchcards are won. Including the original set of scratchcards, how many total scratchcards do you destination up with?
 **/
-#[derive(Clone, Debug)]
-struct Card {
-    instance: usize,
-    winning_cards: Vec<usize>,
+/// Number of instances of every card once all copies are won.
+///
+/// Card `i` adds its instance count to each of the next `matches` cards. Instead of touching
+/// every one of them, the count is added to a running total when card `i` is processed and
+/// taken off again at the first card past its range, so the whole cascade is linear in the
+/// number of cards. Ranges running past the end of the table are cut off there.
+pub fn instance_counts(cards: &[Scratchcard]) -> Vec<usize> {
+    let mut counts: Vec<usize> = Vec::with_capacity(cards.len());
+    let mut expiring = vec![0; cards.len() + 1];
+    let mut running = 0;
+    for (index, card) in cards.iter().enumerate() {
+        running -= expiring[index];
+        let instances = 1 + running;
+        counts.push(instances);
+        let end = (index + 1 + card.matches()).min(cards.len());
+        if end > index + 1 {
+            running += instances;
+            expiring[end] += instances;
+        }
+    }
+    counts
 }
 
 pub fn solve_the_puzzle_4_2() {
@@ -119,60 +314,165 @@ pub fn solve_the_puzzle_4_2() {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(schematic) => {
-            let mut total_of_card = 0;
-            let mut scratchcards_map: HashMap<usize, Card> = HashMap::new();
-            let lines: Vec<&str> = schematic.lines().collect();
-            for (index, line) in lines.iter().enumerate() {
-                let mut numbers: Vec<i32> = vec![];
-                let mut winning_map: HashMap<i32, i32> = HashMap::new();
-                let parts: Vec<&str> = line.split(':').collect();
-                let number_parts: Vec<&str> = parts[1].split('|').collect();
-                let number_vec: Vec<&str> = number_parts[1].split_whitespace().collect();
-                for np in number_vec {
-                    let value: i32 = np.parse::<i32>().unwrap();
-                    numbers.push(value);
-                }
-                let winning_vec: Vec<&str> = number_parts[0].split_whitespace().collect();
-                for wn in winning_vec {
-                    let value: i32 = wn.parse::<i32>().unwrap();
-                    winning_map.insert(value, 0);
-                }
-                let mut winning_cards: Vec<usize> = vec![];
-                let mut next_card = index;
-                for num in numbers {
-                    if winning_map.contains_key(&num) {
-                        next_card += 1;
-                        winning_cards.push(next_card);
-                    }
-                }
-                let card = Card { instance: 1, winning_cards };
-                scratchcards_map.insert(index, card);
-                total_of_card = index + 1;
+        Ok(input) => match parse_scratchcards(&input, Duplicates::Reject) {
+            Err(error) => {
+                println!("error {}", error)
             }
-            //println!("{}", total_of_card);
-            //println!("{:?}", scratchcards_map);
-
-            // Iterate over the vector to modify the cards in the original map
-            for card_index in 0..total_of_card {
-                if let Some(card) = scratchcards_map.get_mut(&card_index).map(|c| (*c).clone()) {
-                    //println!("{:?}",card);
-                    let winning_cards = &card.winning_cards;
-                    for &c in winning_cards {
-                        scratchcards_map.entry(c).and_modify(|next_card| {
-                            // You can modify the fields of the struct here
-                            next_card.instance += card.instance;
-                            //println!("{}:{}:{:?}", c, next_card.instance, next_card.winning_cards);
-                        });
-                    }
-                }
+            Ok(cards) => {
+                let sum: usize = instance_counts(&cards).iter().sum();
+                println!("{}", sum);
             }
-            let mut sum =0;
-            for (_k,v) in scratchcards_map.iter() {
-                sum += v.instance;
+        },
+    }
+}
+
+/// How one card takes part in the cascade: the cards its matches copy, how many instances of
+/// it end up in the pile and the running total of cards up to and including it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CascadeStep {
+    pub id: usize,
+    pub matches: usize,
+    pub copies: Vec<usize>,
+    pub instances: usize,
+    pub cumulative: usize,
+}
+
+/// Cards must be ordered by id, as returned by `parse_scratchcards`.
+pub fn explain_cascade(cards: &[Scratchcard]) -> Vec<CascadeStep> {
+    let mut cumulative = 0;
+    cards
+        .iter()
+        .zip(instance_counts(cards))
+        .enumerate()
+        .map(|(index, (card, instances))| {
+            cumulative += instances;
+            let end = (index + 1 + card.matches()).min(cards.len());
+            CascadeStep {
+                id: card.id,
+                matches: card.matches(),
+                copies: cards[index + 1..end].iter().map(|copy| copy.id).collect(),
+                instances,
+                cumulative,
+            }
+        })
+        .collect()
+}
+
+pub fn render_cascade_table(steps: &[CascadeStep]) -> String {
+    let mut out = format!("{:>6} {:>7} {:<16} {:>10} {:>10}\n", "card", "matches", "copies", "instances", "total");
+    for step in steps {
+        let copies = match (step.copies.first(), step.copies.last()) {
+            (Some(first), Some(last)) if first == last => format!("{}", first),
+            (Some(first), Some(last)) => format!("{}-{}", first, last),
+            _ => "-".to_string(),
+        };
+        out.push_str(&format!(
+            "{:>6} {:>7} {:<16} {:>10} {:>10}\n",
+            step.id, step.matches, copies, step.instances, step.cumulative
+        ));
+    }
+    out
+}
+
+/// A Graphviz graph with an edge from every card to each card it copies, labelled with the
+/// number of copies it hands out (one per instance of the winning card).
+pub fn render_cascade_dot(steps: &[CascadeStep]) -> String {
+    let mut out = String::from("digraph scratchcards {\n    rankdir=LR;\n    node [shape=box];\n");
+    for step in steps {
+        out.push_str(&format!(
+            "    card{} [label=\"Card {}\\n{} matches\\n{} instances\"];\n",
+            step.id, step.id, step.matches, step.instances
+        ));
+    }
+    for step in steps {
+        for copy in &step.copies {
+            out.push_str(&format!("    card{} -> card{} [label=\"{}\"];\n", step.id, copy, step.instances));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[derive(PartialEq, Eq)]
+enum Output {
+    Totals,
+    Table,
+    Dot,
+}
+
+/// Runs both scratchcard parts, or explains the cascade as a table or Graphviz graph:
+/// `day_4 [explain [--dot]] [--duplicates reject|once|each] [--input PATH]`.
+pub fn run(args: &[String]) {
+    let mut duplicates = Duplicates::Reject;
+    let mut output = Output::Totals;
+    let parsed = parse::parse_args(args, "inputs/day_4_1.txt", |arg, args| {
+        match arg {
+            "--duplicates" => {
+                let name = args.value(arg, "reject, once or each")?;
+                duplicates = Duplicates::parse(name).ok_or("--duplicates needs reject, once or each")?;
             }
-            println!("{}", sum);
-            //println!("{:?}", scratchcards_map);
+            "explain" => output = Output::Table,
+            "--dot" if output != Output::Totals => output = Output::Dot,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    });
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            println!("error {}", error);
+            return;
+        }
+    };
+
+    match file::read_file(&input) {
+        Err(error) => {
+            println!("error {}", error)
         }
+        Ok(text) => match parse_scratchcards(&text, duplicates) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(cards) => match output {
+                Output::Totals => {
+                    match total_points(&cards) {
+                        Some(points) => println!("Points: {}", points),
+                        None => println!("error points overflow usize"),
+                    }
+                    println!("Scratchcards: {}", instance_counts(&cards).iter().sum::<usize>());
+                }
+                Output::Table => print!("{}", render_cascade_table(&explain_cascade(&cards))),
+                Output::Dot => print!("{}", render_cascade_dot(&explain_cascade(&cards))),
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_past_the_bitset_limit_are_rejected() {
+        let error = Scratchcard::parse("Card 1: 4000000000 | 4000000000", 1, Duplicates::Reject).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+        assert_eq!(error.message, "number 4000000000 is too large, numbers must be below 65536");
+
+        let card = Scratchcard::parse("Card 1: 65535 | 65535 0", 1, Duplicates::Reject).unwrap();
+        assert_eq!(card.matches(), 1);
+    }
+
+    #[test]
+    fn points_past_usize_are_reported() {
+        let have = vec!["7"; 70].join(" ");
+        let card = Scratchcard::parse(&format!("Card 1: 7 | {}", have), 1, Duplicates::Each).unwrap();
+        assert_eq!(card.matches(), 70);
+        assert_eq!(card.points(), None);
+        assert_eq!(total_points(&[card]), None);
+
+        let have = vec!["7"; 64].join(" ");
+        let card = Scratchcard::parse(&format!("Card 1: 7 | {}", have), 1, Duplicates::Each).unwrap();
+        assert_eq!(card.points(), Some(1 << 63));
+        assert_eq!(total_points(&[card.clone(), Scratchcard { id: 2, ..card }]), None);
     }
 }
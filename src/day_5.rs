@@ -1,5 +1,9 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
+use crate::utils::parse::{self, Cursor, ParseError};
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::Range;
 
 /*
 --- Day 5: If You Give A Seed A Fertilizer ---
@@ -83,6 +87,35 @@ Seed 13, soil 13, fertilizer 52, water 41, light 34, temperature 34, humidity 35
 So, the lowest location number in this example is 35.
 What is the lowest location number that corresponds to any of the initial seed numbers?
 */
+/// Reads numbers separated by spaces up to the end of the line.
+fn parse_numbers(cursor: &mut Cursor) -> Result<Vec<usize>, ParseError> {
+    let mut numbers: Vec<usize> = vec![];
+    cursor.skip_spaces();
+    while !cursor.is_at_end() {
+        numbers.push(cursor.number()?);
+        cursor.skip_spaces();
+    }
+    Ok(numbers)
+}
+
+/// Parses a `seed-to-soil map:` header into an empty map.
+fn parse_header(cursor: &mut Cursor) -> Result<Map, ParseError> {
+    let from = cursor.word()?;
+    cursor.expect("-to-")?;
+    let to = cursor.word()?;
+    cursor.skip_spaces();
+    cursor.expect("map:")?;
+    cursor.skip_spaces();
+    if !cursor.is_at_end() {
+        return Err(cursor.error("expected the end of the line after the map header"));
+    }
+
+    let mut map = Map::new();
+    map.from = String::from(from);
+    map.to = String::from(to);
+    Ok(map)
+}
+
 pub struct MapEntry {
     pub destination_start: usize,
     pub source_start: usize,
@@ -90,38 +123,49 @@ pub struct MapEntry {
 }
 
 impl MapEntry {
-    fn from_str(string: &str) -> MapEntry {
-        let entries: Vec<usize> = string.split_whitespace().map(|s| s.parse().unwrap()).collect();
-
-        return MapEntry {
-            destination_start: entries[0],
-            source_start: entries[1],
-            range: entries[2],
+    /// Parses a `destination_start source_start range` line.
+    fn parse(cursor: &mut Cursor) -> Result<MapEntry, ParseError> {
+        match parse_numbers(cursor)?[..] {
+            [destination_start, source_start, range] => Ok(MapEntry { destination_start, source_start, range }),
+            _ => Err(cursor.error("expected a destination start, a source start and a range length")),
         }
     }
 
+    /// Saturates rather than overflowing; `Map::check` reports entries that would overflow.
+    pub fn source_range(&self) -> Range<usize> {
+        self.source_start..self.source_start.saturating_add(self.range)
+    }
+
+    pub fn destination_range(&self) -> Range<usize> {
+        self.destination_start..self.destination_start.saturating_add(self.range)
+    }
+
     pub fn in_range(&self, value: usize) -> bool {
-        return self.source_start <= value && value < self.source_start + self.range;
+        self.source_range().contains(&value)
     }
 
-    fn find_destination(&self, value: usize) -> usize {
+    /// `None` when the entry would send `value` past the top of `usize`; `map_ranges` drops
+    /// those values the same way.
+    fn find_destination(&self, value: usize) -> Option<usize> {
         if self.in_range(value) {
-            return self.destination_start + value - self.source_start;
+            let destination = self.destination_range();
+            return destination.start.checked_add(value - self.source_start).filter(|d| destination.contains(d));
         }
 
-        return value;
+        return Some(value);
     }
 
     fn destination_in_range(&self, value: usize) -> bool {
-        return self.destination_start <= value && value < self.destination_start + self.range;
+        self.destination_range().contains(&value)
     }
 
-    fn find_source(&self, value: usize) -> usize {
+    fn find_source(&self, value: usize) -> Option<usize> {
         if self.destination_in_range(value) {
-            return self.source_start + value - self.destination_start;
+            let source = self.source_range();
+            return source.start.checked_add(value - self.destination_start).filter(|s| source.contains(s));
         }
 
-        return value;
+        return Some(value);
     }
 }
 
@@ -141,71 +185,539 @@ impl Map {
         }
     }
 
-    fn find_destination(&self, value: usize) -> usize {
-        let mut lowest_destination: Option<usize> = None;
+    /// Should entries overlap, the first one listed maps the overlap. `map_ranges` and
+    /// `PiecewiseMap::from_map` follow the same rule, so every part and query agrees;
+    /// `Map::check` reports almanacs where the rule matters.
+    fn find_destination(&self, value: usize) -> Option<usize> {
+        match self.entries.iter().find(|entry| entry.in_range(value)) {
+            Some(entry) => entry.find_destination(value),
+            None => Some(value),
+        }
+    }
 
+    fn find_source(&self, value: usize) -> Option<usize> {
         for entry in &self.entries {
-            if !entry.in_range(value) {
+            if entry.destination_in_range(value) {
+                return entry.find_source(value);
+            }
+        }
+
+        return Some(value);
+    }
+
+    /// Pushes whole ranges of source values through the map. Every range is split against
+    /// the entries' source ranges: the pieces an entry covers are shifted to its destination
+    /// and whatever no entry covers passes through unchanged. Should entries overlap, the
+    /// first one listed maps the overlap, as in `find_destination`.
+    pub fn map_ranges(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        let mut pending: Vec<Range<usize>> = ranges.iter().filter(|r| !r.is_empty()).cloned().collect();
+        let mut mapped: Vec<Range<usize>> = vec![];
+
+        for entry in &self.entries {
+            let source = entry.source_range();
+            let mut outside: Vec<Range<usize>> = vec![];
+
+            for range in pending {
+                let start = range.start.max(source.start);
+                let end = range.end.min(source.end);
+                if start >= end {
+                    outside.push(range);
+                    continue;
+                }
+                // Clipped to the destination range, so an entry running past `usize::MAX`
+                // drops the values it cannot represent instead of overflowing.
+                let destination = entry.destination_range();
+                let mapped_start = destination.start.saturating_add(start - source.start);
+                let mapped_end = destination.start.saturating_add(end - source.start).min(destination.end);
+                if mapped_start < mapped_end {
+                    mapped.push(mapped_start..mapped_end);
+                }
+                if range.start < start {
+                    outside.push(range.start..start);
+                }
+                if end < range.end {
+                    outside.push(end..range.end);
+                }
+            }
+
+            pending = outside;
+        }
+
+        mapped.extend(pending);
+        mapped
+    }
+}
+
+/// A defect in one almanac map. Entries are numbered from 1, in the order the map lists them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryProblem {
+    Empty { entry: usize },
+    SourceOverflow { entry: usize },
+    DestinationOverflow { entry: usize },
+    OverlappingSources { first: usize, second: usize },
+    OverlappingDestinations { first: usize, second: usize },
+}
+
+impl fmt::Display for EntryProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntryProblem::Empty { entry } => write!(f, "entry {} has a zero-length range", entry),
+            EntryProblem::SourceOverflow { entry } => write!(f, "entry {} source start + range overflows usize", entry),
+            EntryProblem::DestinationOverflow { entry } => {
+                write!(f, "entry {} destination start + range overflows usize", entry)
+            }
+            EntryProblem::OverlappingSources { first, second } => {
+                write!(f, "entries {} and {} have overlapping source ranges", first, second)
+            }
+            EntryProblem::OverlappingDestinations { first, second } => {
+                write!(f, "entries {} and {} have overlapping destination ranges, so the map is not injective", first, second)
+            }
+        }
+    }
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start.max(b.start) < a.end.min(b.end)
+}
+
+impl Map {
+    /// Everything that makes this map's answer depend on how overlaps are resolved, or on
+    /// arithmetic that would overflow.
+    pub fn check(&self) -> Vec<EntryProblem> {
+        let mut problems: Vec<EntryProblem> = vec![];
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let number = index + 1;
+            if entry.range == 0 {
+                problems.push(EntryProblem::Empty { entry: number });
+            }
+            if entry.source_start.checked_add(entry.range).is_none() {
+                problems.push(EntryProblem::SourceOverflow { entry: number });
+            }
+            if entry.destination_start.checked_add(entry.range).is_none() {
+                problems.push(EntryProblem::DestinationOverflow { entry: number });
+            }
+        }
+
+        for (first, a) in self.entries.iter().enumerate() {
+            for (second, b) in self.entries.iter().enumerate().skip(first + 1) {
+                if overlaps(&a.source_range(), &b.source_range()) {
+                    problems.push(EntryProblem::OverlappingSources { first: first + 1, second: second + 1 });
+                }
+                if overlaps(&a.destination_range(), &b.destination_range()) {
+                    problems.push(EntryProblem::OverlappingDestinations { first: first + 1, second: second + 1 });
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+pub struct Almanac {
+    pub seeds: Vec<usize>,
+    pub maps: Vec<Map>,
+}
+
+impl Almanac {
+    /// Parses a `seeds: ...` line followed by maps, each a header line and its entries.
+    pub fn parse(input: &str) -> Result<Almanac, ParseError> {
+        let mut lines = input.lines().enumerate().map(|(index, text)| (index + 1, text.trim_end_matches('\r')));
+
+        let (line, text) = lines.next().unwrap_or((1, ""));
+        let mut cursor = Cursor::new(text, line);
+        cursor.expect("seeds:")?;
+        let seeds = parse_numbers(&mut cursor)?;
+
+        let mut maps: Vec<Map> = vec![];
+
+        for (line, text) in lines {
+            let mut cursor = Cursor::new(text, line);
+            cursor.skip_spaces();
+            if cursor.is_at_end() {
                 continue;
             }
 
-            let destination = entry.find_destination(value);
+            if cursor.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                maps.push(parse_header(&mut cursor)?);
+            } else {
+                match maps.last_mut() {
+                    Some(map) => map.entries.push(MapEntry::parse(&mut cursor)?),
+                    None => return Err(cursor.error("map entry before any map header")),
+                }
+            }
+        }
+
+        Ok(Almanac { seeds, maps })
+    }
 
-            if lowest_destination.is_none() || lowest_destination.unwrap() > destination {
-                lowest_destination = Some(destination);
+    /// Part two reads the seed line as `start length` pairs. Ranges saturate at the top of
+    /// `usize`, like `MapEntry::source_range`.
+    pub fn seed_ranges(&self) -> Vec<Range<usize>> {
+        self.seeds
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| pair[0]..pair[0].saturating_add(pair[1]))
+            .collect()
+    }
+
+    fn check_category(&self, name: &str) -> Result<(), String> {
+        if self.maps.iter().any(|map| map.from == name || map.to == name) {
+            Ok(())
+        } else {
+            Err(format!("unknown category {}", name))
+        }
+    }
+
+    /// The maps leading from the `from` category to `to`, found by following the map headers
+    /// rather than the order the maps appear in. Should the almanac offer several chains, the
+    /// shortest one wins.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Vec<&Map>, String> {
+        self.check_category(from)?;
+        self.check_category(to)?;
+
+        let mut reached_by: HashMap<&str, &Map> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut chain: Vec<&Map> = vec![];
+                let mut current = to;
+                while current != from {
+                    let map = reached_by[current];
+                    chain.push(map);
+                    current = &map.from;
+                }
+                chain.reverse();
+                return Ok(chain);
+            }
+
+            for map in self.maps.iter().filter(|map| map.from == category) {
+                if map.to != from && !reached_by.contains_key(map.to.as_str()) {
+                    reached_by.insert(&map.to, map);
+                    queue.push_back(&map.to);
+                }
             }
         }
 
-        return lowest_destination.unwrap_or(value);
+        Err(format!("no maps lead from {} to {}", from, to))
     }
 
-    fn find_source(&self, value: usize) -> usize {
-        for entry in &self.entries {
-            if entry.destination_in_range(value) {
-                return entry.find_source(value);
+    /// Every problem of every map, tagged with the map it was found in.
+    pub fn check(&self) -> Vec<(&Map, EntryProblem)> {
+        self.maps.iter().flat_map(|map| map.check().into_iter().map(move |problem| (map, problem))).collect()
+    }
+
+    /// Every `from` value that the maps send into one of the `to` category's `ranges`.
+    pub fn preimage(&self, ranges: &[Range<usize>], from: &str, to: &str) -> Result<Vec<Range<usize>>, String> {
+        Ok(PiecewiseMap::from_maps(&self.chain(from, to)?).preimage(ranges))
+    }
+
+    /// Converts `value` of the `from` category into the `to` category. When the maps only lead
+    /// the other way they are run backwards, so "seed for location 35" works as well as
+    /// "humidity for seed 79".
+    pub fn convert(&self, value: usize, from: &str, to: &str) -> Result<usize, String> {
+        let converted = match self.chain(from, to) {
+            Ok(chain) => chain.iter().try_fold(value, |value, map| map.find_destination(value)),
+            Err(error) => match self.chain(to, from) {
+                Ok(chain) => chain.iter().rev().try_fold(value, |value, map| map.find_source(value)),
+                Err(_) => return Err(error),
+            },
+        };
+        converted.ok_or_else(|| format!("{} {} has no {}: a map sends it past the top of usize", from, value, to))
+    }
+}
+
+/// Lowest location reachable from any of the seed ranges, found by mapping whole ranges
+/// instead of single seeds.
+pub fn get_lowest_location_for_ranges(ranges: &[Range<usize>], maps: &[&Map]) -> Option<usize> {
+    let mut ranges = ranges.to_vec();
+
+    for map in maps {
+        ranges = map.map_ranges(&ranges);
+    }
+
+    ranges.iter().map(|range| range.start).min()
+}
+
+/// Sorts `ranges` and joins the ones that overlap or touch, dropping empty ones.
+pub fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// How one map layer treats the values that reach it: `shifted` values land somewhere else,
+/// `unchanged` ones come out as they went in, whether or not an entry covered them. Values
+/// the layer drops past the top of `usize` count as neither.
+pub struct LayerStats<'a> {
+    pub map: &'a Map,
+    pub ranges: usize,
+    pub values: usize,
+    pub shifted: usize,
+    pub unchanged: usize,
+}
+
+/// Follows `ranges` through every map in turn, recording what each layer does with them.
+pub fn layer_stats<'a>(ranges: &[Range<usize>], maps: &[&'a Map]) -> Vec<LayerStats<'a>> {
+    let mut ranges = merge_ranges(ranges.to_vec());
+    let mut stats: Vec<LayerStats> = vec![];
+
+    for map in maps {
+        let layer = PiecewiseMap::from_map(map);
+        let mut shifted = 0;
+        let mut unchanged = 0;
+
+        for range in &ranges {
+            for segment in layer.segments() {
+                let overlap = segment.end.min(range.end).saturating_sub(segment.start.max(range.start));
+                if segment.is_identity() {
+                    unchanged += overlap;
+                } else {
+                    shifted += overlap;
+                }
             }
         }
 
-        return value;
+        stats.push(LayerStats {
+            map,
+            ranges: ranges.len(),
+            values: ranges.iter().map(|range| range.len()).sum(),
+            shifted,
+            unchanged,
+        });
+        ranges = merge_ranges(map.map_ranges(&ranges));
     }
+
+    stats
 }
 
-pub fn solve_the_puzzle_5_1() {
-    match file::read_file("inputs/day_5_1.txt") {
-        Err(error) => {
-            println!("error {}", error)
+/// One piece of a piecewise-linear mapping: every value in `start..end` maps to `value + offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: usize,
+    pub end: usize,
+    pub offset: i128,
+}
+
+impl Segment {
+    pub fn is_identity(&self) -> bool {
+        self.offset == 0
+    }
+
+    fn apply(&self, value: usize) -> Option<usize> {
+        usize::try_from(value as i128 + self.offset).ok()
+    }
+
+    /// Where the segment's values land, or `None` if that leaves the domain. Segments are
+    /// clipped when a `PiecewiseMap` is built, so theirs always fit.
+    pub fn image(&self) -> Option<Range<usize>> {
+        Some(self.apply(self.start)?..self.apply(self.end)?)
+    }
+}
+
+/// A mapping of the `0..usize::MAX` domain as sorted, non-overlapping segments. Values no map
+/// entry covers get explicit identity segments, and neighbouring segments with the same offset
+/// are merged, so equal mappings always have equal segments. Values a map would send past the
+/// top of `usize` are dropped, as in `Map::map_ranges`, and fall in no segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    segments: Vec<Segment>,
+}
+
+impl PiecewiseMap {
+    pub fn identity() -> PiecewiseMap {
+        PiecewiseMap { segments: vec![Segment { start: 0, end: usize::MAX, offset: 0 }] }
+    }
+
+    /// Should entries overlap, the first one listed maps the overlap, as in
+    /// `Map::find_destination`.
+    pub fn from_map(map: &Map) -> PiecewiseMap {
+        let mut result = PiecewiseMap::identity();
+
+        for entry in map.entries.iter().rev() {
+            let source = entry.source_range();
+            let kept = source.start..source.start + source.len().min(entry.destination_range().len());
+            result.assign(kept.end..source.end, None);
+            result.assign(kept, Some(entry.destination_start as i128 - entry.source_start as i128));
         }
-        Ok(input) => {
-            let mut lines = input.lines();
-            let x = lines.nth(0).unwrap();
 
-            let seeds: Vec<usize> = x[7..].split_whitespace().map(|s| s.parse().unwrap()).collect();
+        result.normalise();
+        result
+    }
 
-            lines.next();
+    /// The whole chain of maps, applied in order, as a single mapping.
+    pub fn from_maps(maps: &[&Map]) -> PiecewiseMap {
+        maps.iter().fold(PiecewiseMap::identity(), |composed, map| composed.then(&PiecewiseMap::from_map(map)))
+    }
 
-            let mut maps: Vec<Map> = vec![];
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
 
-            for line in lines {
-                if line.contains("map") {
-                    let name: Vec<&str> = line.split(" ").nth(0).unwrap().split('-').collect();
+    /// Overwrites the offset of `range`, splitting the segments it cuts through. With no
+    /// offset the range is cut out and its values are dropped.
+    fn assign(&mut self, range: Range<usize>, offset: Option<i128>) {
+        if range.is_empty() {
+            return;
+        }
 
-                    let mut map = Map::new();
-                    map.from = String::from(name[0]);
-                    map.to = String::from(name[2]);
+        let mut segments: Vec<Segment> = Vec::with_capacity(self.segments.len() + 2);
+        for segment in self.segments.iter().filter(|s| s.start < range.start) {
+            segments.push(Segment { end: segment.end.min(range.start), ..*segment });
+        }
+        if let Some(offset) = offset {
+            segments.push(Segment { start: range.start, end: range.end, offset });
+        }
+        for segment in self.segments.iter().filter(|s| s.end > range.end) {
+            segments.push(Segment { start: segment.start.max(range.end), ..*segment });
+        }
+
+        self.segments = segments;
+    }
+
+    fn normalise(&mut self) {
+        let mut segments: Vec<Segment> = Vec::with_capacity(self.segments.len());
+
+        for segment in self.segments.iter().filter(|s| s.start < s.end) {
+            match segments.last_mut() {
+                Some(last) if last.offset == segment.offset && last.end == segment.start => last.end = segment.end,
+                _ => segments.push(*segment),
+            }
+        }
+
+        self.segments = segments;
+    }
 
-                    maps.push(map);
-                } else if !line.is_empty() {
-                    maps.last_mut().unwrap().entries.push(MapEntry::from_str(line));
+    fn segment_of(&self, value: usize) -> Option<&Segment> {
+        let index = self.segments.partition_point(|s| s.end <= value);
+        self.segments.get(index).filter(|s| s.start <= value)
+    }
+
+    /// Maps a single value with one binary search, or `None` for a dropped value. `usize::MAX`
+    /// itself lies outside the domain and is returned unchanged, as `Map::find_destination` does.
+    pub fn apply(&self, value: usize) -> Option<usize> {
+        match self.segment_of(value) {
+            Some(segment) => segment.apply(value),
+            None if value == usize::MAX => Some(value),
+            None => None,
+        }
+    }
+
+    /// This mapping followed by `next`. Each segment's image is cut at `next`'s segment
+    /// boundaries, so the result has at most one segment per pair of overlapping pieces.
+    pub fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut segments: Vec<Segment> = vec![];
+
+        for segment in &self.segments {
+            let Some(image) = segment.image() else { continue };
+            let first = next.segments.partition_point(|s| s.end <= image.start);
+
+            // Values `next` drops fall between its segments and are dropped here too.
+            for piece in next.segments[first..].iter().take_while(|s| s.start < image.end) {
+                let start = image.start.max(piece.start);
+                let end = image.end.min(piece.end);
+                segments.push(Segment {
+                    start: segment.start + (start - image.start),
+                    end: segment.start + (end - image.start),
+                    offset: segment.offset + piece.offset,
+                });
+            }
+        }
+
+        let mut composed = PiecewiseMap { segments };
+        composed.normalise();
+        composed
+    }
+
+    /// Exactly the values whose image falls in one of `ranges`, as sorted, disjoint ranges.
+    /// Unlike `inverse` this works for maps that are not bijections too.
+    pub fn preimage(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        let targets = merge_ranges(ranges.to_vec());
+        let mut preimage: Vec<Range<usize>> = vec![];
+
+        for segment in &self.segments {
+            let Some(image) = segment.image() else { continue };
+
+            for target in &targets {
+                let start = image.start.max(target.start);
+                let end = image.end.min(target.end);
+                if start < end {
+                    preimage.push(segment.start + (start - image.start)..segment.start + (end - image.start));
                 }
+            }
+        }
+
+        merge_ranges(preimage)
+    }
+
+    /// The inverse mapping, or `None` when this one is not a bijection of the domain (some
+    /// values are dropped, or hit twice or never).
+    pub fn inverse(&self) -> Option<PiecewiseMap> {
+        if self.segments.windows(2).any(|pair| pair[0].end != pair[1].start) {
+            return None;
+        }
 
+        let mut segments: Vec<Segment> = vec![];
+        for segment in &self.segments {
+            let image = segment.image()?;
+            segments.push(Segment { start: image.start, end: image.end, offset: -segment.offset });
+        }
+
+        segments.sort_by_key(|s| s.start);
+        let mut expected = 0;
+        for segment in &segments {
+            if segment.start != expected {
+                return None;
             }
-            let mut lowest_seed: usize = usize::MAX;
+            expected = segment.end;
+        }
+        if expected != usize::MAX {
+            return None;
+        }
+
+        let mut inverse = PiecewiseMap { segments };
+        inverse.normalise();
+        Some(inverse)
+    }
+}
 
-            for seed in seeds {
-                let location = get_lowest_location_for_seed(seed, &maps);
+pub fn solve_the_puzzle_5_1() {
+    match file::read_file("inputs/day_5_1.txt") {
+        Err(error) => {
+            println!("error {}", error)
+        }
+        Ok(input) => {
+            let almanac = match Almanac::parse(&input) {
+                Ok(almanac) => almanac,
+                Err(error) => {
+                    println!("error {}", error);
+                    return;
+                }
+            };
+            let chain = match almanac.chain("seed", "location") {
+                Ok(chain) => chain,
+                Err(error) => {
+                    println!("error {}", error);
+                    return;
+                }
+            };
+            let mut lowest_seed: usize = usize::MAX;
 
-                if location < lowest_seed {
-                    lowest_seed = location;
+            for &seed in &almanac.seeds {
+                // Seeds a map sends past the top of `usize` have no location.
+                if let Some(location) = get_lowest_location_for_seed(seed, &chain) {
+                    if location < lowest_seed {
+                        lowest_seed = location;
+                    }
                 }
             }
 
@@ -221,62 +733,269 @@ pub fn solve_the_puzzle_5_2() {
             println!("error {}", error)
         }
         Ok(input) => {
-            let mut lines = input.lines();
-            let x = lines.nth(0).unwrap();
+            let almanac = match Almanac::parse(&input) {
+                Ok(almanac) => almanac,
+                Err(error) => {
+                    println!("error {}", error);
+                    return;
+                }
+            };
+
+            match almanac.chain("seed", "location") {
+                Err(error) => println!("error {}", error),
+                Ok(chain) => match get_lowest_location_for_ranges(&almanac.seed_ranges(), &chain) {
+                    Some(location) => println!("{}", location),
+                    None => println!("error no seed in the seed ranges has a location"),
+                },
+            }
+        }
+    }
+}
 
-            let seeds: Vec<usize> = x[7..].split_whitespace().map(|s| s.parse().unwrap()).collect();
+pub fn get_seed_for_destination(destination: usize, maps: &[&Map]) -> Option<usize> {
+    let mut value = destination;
 
-            lines.next();
+    for map in maps.iter().rev() {
+        value = map.find_source(value)?;
+    }
 
-            let mut maps: Vec<Map> = vec![];
+    return Some(value);
+}
 
-            for line in lines {
-                if line.contains("map") {
-                    let name: Vec<&str> = line.split(" ").nth(0).unwrap().split('-').collect();
+pub fn get_lowest_location_for_seed(seed: usize, maps: &[&Map]) -> Option<usize> {
+    let mut value = seed;
 
-                    let mut map = Map::new();
-                    map.from = String::from(name[0]);
-                    map.to = String::from(name[2]);
+    for map in maps {
+        value = map.find_destination(value)?;
+    }
 
-                    maps.push(map);
-                } else if !line.is_empty() {
-                    maps.last_mut().unwrap().entries.push(MapEntry::from_str(line));
+    return Some(value);
+}
+
+enum Output {
+    Lowest,
+    Compose,
+    Check,
+    Layers,
+    Inverse { start: usize, length: usize },
+    Query { target: String, source: String, value: usize },
+}
+
+/// Runs both almanac parts, prints the seed-to-location chain composed into one
+/// piecewise-linear mapping, lists the almanac's consistency problems, follows the seed ranges
+/// layer by layer, finds the seed ranges landing in a location range, or converts a single
+/// value between two categories: `day_5 [compose | check | layers | inverse START LENGTH |
+/// query TARGET SOURCE VALUE] [--input PATH]`, e.g. `query humidity seed 79`.
+pub fn run(args: &[String]) {
+    let mut output = Output::Lowest;
+    let parsed = parse::parse_args(args, "inputs/day_5_1.txt", |arg, args| {
+        match arg {
+            "compose" => output = Output::Compose,
+            "check" => output = Output::Check,
+            "layers" => output = Output::Layers,
+            "inverse" => {
+                let start = args.parsed(arg, "a location start and length")?;
+                let length = args.parsed(arg, "a location start and length")?;
+                output = Output::Inverse { start, length };
+            }
+            "query" => {
+                let what = "a target category, a source category and a number";
+                let target = args.value(arg, what)?.to_string();
+                let source = args.value(arg, what)?.to_string();
+                let value = args.parsed(arg, what)?;
+                output = Output::Query { target, source, value };
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    });
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            println!("error {}", error);
+            return;
+        }
+    };
+
+    match file::read_file(&input) {
+        Err(error) => {
+            println!("error {}", error)
+        }
+        Ok(text) => {
+            let almanac = match Almanac::parse(&text) {
+                Ok(almanac) => almanac,
+                Err(error) => {
+                    println!("error {}", error);
+                    return;
                 }
+            };
 
+            if let Output::Check = output {
+                let problems = almanac.check();
+                for (map, problem) in &problems {
+                    println!("{}-to-{} map: {}", map.from, map.to, problem);
+                }
+                if problems.is_empty() {
+                    println!("No problems in {} maps", almanac.maps.len());
+                }
+                return;
+            }
+
+            if let Output::Query { target, source, value } = &output {
+                match almanac.convert(*value, source, target) {
+                    Ok(converted) => println!("{} {} is {} {}", source, value, target, converted),
+                    Err(error) => println!("error {}", error),
+                }
+                return;
+            }
+
+            let chain = match almanac.chain("seed", "location") {
+                Ok(chain) => chain,
+                Err(error) => {
+                    println!("error {}", error);
+                    return;
+                }
+            };
+            let composed = PiecewiseMap::from_maps(&chain);
+
+            if let Output::Layers = output {
+                println!("{:<28} {:>7} {:>14} {:>14} {:>14}", "map", "ranges", "values", "shifted", "unchanged");
+                for layer in layer_stats(&almanac.seed_ranges(), &chain) {
+                    let name = format!("{}-to-{}", layer.map.from, layer.map.to);
+                    println!(
+                        "{:<28} {:>7} {:>14} {:>14} {:>14}",
+                        name, layer.ranges, layer.values, layer.shifted, layer.unchanged
+                    );
+                }
+                return;
             }
-            let seed_pairs: Vec<(usize, usize)> = (0..seeds.len()).step_by(2).map(|i| (seeds[i], seeds[i] + seeds[i+1])).collect();
-            let highest_seed: usize = seed_pairs.iter().map(|s| s.1).max().unwrap();
 
-            for location in 0..highest_seed {
-                let seed = get_seed_for_destination(location, &maps);
+            if let Output::Inverse { start, length } = output {
+                let locations = start..start.saturating_add(length);
+                for range in composed.preimage(std::slice::from_ref(&locations)) {
+                    println!("seeds {} .. {} ({} values)", range.start, range.end, range.len());
+                }
+                return;
+            }
 
-                if seed_pairs.iter().find(|sp| sp.0 <= seed && seed < sp.1).is_some() {
-                    println!("{}", location);
-                    break
+            if let Output::Compose = output {
+                for segment in composed.segments() {
+                    if segment.is_identity() {
+                        println!("{:>20} .. {:<20} identity", segment.start, segment.end);
+                    } else {
+                        println!("{:>20} .. {:<20} {:+}", segment.start, segment.end, segment.offset);
+                    }
                 }
+                return;
+            }
+
+            let lowest = almanac.seeds.iter().filter_map(|&seed| composed.apply(seed)).min();
+            println!("Lowest location for seeds: {}", lowest.map_or("none".to_string(), |l| l.to_string()));
+            match get_lowest_location_for_ranges(&almanac.seed_ranges(), &chain) {
+                Some(location) => println!("Lowest location for seed ranges: {}", location),
+                None => println!("error no seed in the seed ranges has a location"),
             }
         }
     }
 }
 
-pub fn get_seed_for_destination(destination: usize, maps: &Vec<Map>) -> usize {
-    let mut value = destination;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for map in maps.iter().rev() {
-        value = map.find_source(value);
+    const OVERLAPPING: &str = "seeds: 5 1\n\nseed-to-location map:\n100 0 10\n50 5 10\n";
+
+    const PAST_THE_TOP: &str = "seeds: 7 1\n\n\
+        seed-to-soil map:\n18446744073709551610 0 10\n\n\
+        soil-to-location map:\n0 18446744073709551600 12\n";
+
+    /// Every way of mapping a single seed gives the same location, or drops it everywhere.
+    fn assert_every_path_agrees(almanac: &Almanac, seeds: Range<usize>) {
+        let chain = almanac.chain("seed", "location").unwrap();
+        let composed = PiecewiseMap::from_maps(&chain);
+
+        for seed in seeds {
+            let location = get_lowest_location_for_seed(seed, &chain);
+            assert_eq!(composed.apply(seed), location, "seed {}", seed);
+            assert_eq!(almanac.convert(seed, "seed", "location").ok(), location, "seed {}", seed);
+            assert_eq!(get_lowest_location_for_ranges(&[seed..seed + 1], &chain), location, "seed {}", seed);
+            assert_eq!(composed.preimage(&[0..usize::MAX]).iter().any(|range| range.contains(&seed)), location.is_some());
+        }
     }
 
-    return value;
-}
+    #[test]
+    fn overlapping_entries_resolve_the_same_way_everywhere() {
+        let almanac = Almanac::parse(OVERLAPPING).unwrap();
+        assert_every_path_agrees(&almanac, 0..20);
+        assert_eq!(PiecewiseMap::from_maps(&almanac.chain("seed", "location").unwrap()).apply(5), Some(105));
+    }
 
-pub fn get_lowest_location_for_seed(seed: usize, maps: &Vec<Map>) -> usize {
-    let mut value = seed;
+    #[test]
+    fn destinations_past_the_top_of_usize_are_dropped_everywhere() {
+        let almanac = Almanac::parse(PAST_THE_TOP).unwrap();
+        assert_every_path_agrees(&almanac, 0..20);
+
+        let composed = PiecewiseMap::from_maps(&almanac.chain("seed", "location").unwrap());
+        assert_eq!(composed.apply(7), None);
+        assert_eq!(composed.apply(4), Some(usize::MAX - 1));
+        assert_eq!(composed.apply(0), Some(10));
+        assert_eq!(composed.apply(usize::MAX), Some(usize::MAX));
+        assert_eq!(composed.inverse(), None);
+    }
 
-    for map in maps {
-        value = map.find_destination(value);
+    #[test]
+    fn map_ranges_clips_at_the_top_of_usize() {
+        let map = Map {
+            from: "seed".to_string(),
+            to: "location".to_string(),
+            entries: vec![MapEntry { destination_start: usize::MAX - 5, source_start: 0, range: 10 }],
+        };
+        assert_eq!(map.map_ranges(&[0..10]), vec![usize::MAX - 5..usize::MAX]);
     }
 
-    return value;
-}
+    #[test]
+    fn single_values_past_the_top_of_usize_are_dropped() {
+        let map = Map {
+            from: "seed".to_string(),
+            to: "location".to_string(),
+            entries: vec![MapEntry { destination_start: usize::MAX - 5, source_start: 0, range: 10 }],
+        };
+        assert_eq!(map.find_destination(4), Some(usize::MAX - 1));
+        assert_eq!(map.find_destination(5), None);
+        assert_eq!(map.find_destination(7), None);
+        assert_eq!(map.find_destination(10), Some(10));
+        assert_eq!(map.find_source(usize::MAX - 1), Some(4));
+
+        let reversed = Map {
+            from: "seed".to_string(),
+            to: "location".to_string(),
+            entries: vec![MapEntry { destination_start: 0, source_start: usize::MAX - 5, range: 10 }],
+        };
+        assert_eq!(reversed.find_source(4), Some(usize::MAX - 1));
+        assert_eq!(reversed.find_source(7), None);
+    }
 
+    #[test]
+    fn malformed_almanacs_are_reported() {
+        let error = |text: &str| Almanac::parse(text).err().map(|error| (error.line, error.message));
+        assert_eq!(error("seed: 1 2\n"), Some((1, "expected 'seeds:'".to_string())));
+        assert_eq!(error("seeds: 1 x\n"), Some((1, "expected a number".to_string())));
+        assert_eq!(error("seeds: 1\n\n1 2 3\n"), Some((3, "map entry before any map header".to_string())));
+        assert_eq!(error("seeds: 1\nseed-soil map:\n"), Some((2, "expected '-to-'".to_string())));
+        assert_eq!(
+            error("seeds: 1\nseed-to-soil map:\n1 2\n"),
+            Some((3, "expected a destination start, a source start and a range length".to_string()))
+        );
+        assert_eq!(
+            error("seeds: 99999999999999999999\n"),
+            Some((1, "number 99999999999999999999 is too large".to_string()))
+        );
+        assert_eq!(error(""), Some((1, "expected 'seeds:'".to_string())));
+    }
 
+    #[test]
+    fn seed_ranges_saturate_at_the_top_of_usize() {
+        let almanac = Almanac::parse("seeds: 18446744073709551610 10 1 2 7\n").unwrap();
+        assert_eq!(almanac.seed_ranges(), vec![usize::MAX - 5..usize::MAX, 1..3]);
+    }
+}
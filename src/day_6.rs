@@ -1,5 +1,10 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::big::BigUint;
+use crate::utils::file;
+use crate::utils::number::{self, Integer};
+use crate::utils::parse::{self, Cursor, ParseError};
+
+use std::fmt;
+use std::str::FromStr;
 
 /*
 --- Day 6: Wait For It ---
@@ -37,79 +42,227 @@ In the third race, you could hold the button for at least 11 milliseconds and no
 To see how much margin of error you have, determine the number of ways you can beat the record in each race; in this example, if you multiply these values together, you get 288 (4 * 8 * 9).
 Determine the number of ways you could beat the record in each race. What do you get if you multiply these numbers together?
 */
+/// The arithmetic the race maths needs, so records can be solved in `usize`, `u128` or
+/// arbitrary precision. The checked operations return `None` where a primitive would wrap.
+pub trait RaceNumber: Clone + Ord + FromStr + fmt::Display {
+    const NAME: &'static str;
+
+    fn small(value: u8) -> Self;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    fn half(&self) -> Self;
+    fn isqrt(&self) -> Self;
+}
+
+macro_rules! impl_race_number {
+    ($($t:ty),*) => {$(
+        impl RaceNumber for $t {
+            const NAME: &'static str = stringify!($t);
+
+            fn small(value: u8) -> Self { value as $t }
+            fn checked_add(&self, rhs: &Self) -> Option<Self> { Integer::checked_add(*self, *rhs) }
+            fn checked_sub(&self, rhs: &Self) -> Option<Self> { Integer::checked_sub(*self, *rhs) }
+            fn checked_mul(&self, rhs: &Self) -> Option<Self> { Integer::checked_mul(*self, *rhs) }
+            fn half(&self) -> Self { *self / 2 }
+            fn isqrt(&self) -> Self { number::isqrt(*self) }
+        }
+    )*};
+}
+
+impl_race_number!(usize, u128);
+
+impl RaceNumber for BigUint {
+    const NAME: &'static str = "big integers";
+
+    fn small(value: u8) -> Self {
+        BigUint::from(value as u32)
+    }
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        BigUint::checked_sub(self, rhs)
+    }
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+    fn half(&self) -> Self {
+        self >> 1
+    }
+    fn isqrt(&self) -> Self {
+        BigUint::isqrt(self)
+    }
+}
+
+fn overflow<T: RaceNumber>(what: &str) -> String {
+    format!("{} overflows {}", what, T::NAME)
+}
+
 #[derive(Debug)]
-struct RaceRecord {
-    time: usize,
-    distance: usize,
-}
-impl RaceRecord {
-    fn from(time: usize, distance: usize) -> RaceRecord {
-        return RaceRecord {
-            time,
-            distance,
+pub struct RaceRecord<T = usize> {
+    pub time: T,
+    pub distance: T,
+}
+/// The hold times that beat a record: every one from `first` to `last` inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinningHolds<T = usize> {
+    pub first: T,
+    pub last: T,
+    pub count: T,
+}
+
+impl<T: RaceNumber> RaceRecord<T> {
+    /// `hold` must not exceed the race time. A product too large for `T` is certainly larger
+    /// than the distance, which does fit.
+    fn beats(&self, hold: &T) -> bool {
+        let moving = self.time.checked_sub(hold).expect("hold time is longer than the race");
+        hold.checked_mul(&moving).is_none_or(|travelled| travelled > self.distance)
+    }
+
+    /// Holding the button for `h` ms travels `h * (T - h)`, which beats `D` strictly between
+    /// the roots of `h^2 - T*h + D = 0`. The integer square root of the discriminant puts the
+    /// lower root within one step, and checking the neighbours settles it exactly; the upper
+    /// one follows by symmetry. `Ok(None)` when no hold time wins, and an error when `T*T` or
+    /// `4*D` does not fit.
+    pub fn winning_holds(&self) -> Result<Option<WinningHolds<T>>, String> {
+        let one = T::small(1);
+        let square = self.time.checked_mul(&self.time).ok_or_else(|| overflow::<T>("time * time"))?;
+        let quadruple = T::small(4).checked_mul(&self.distance).ok_or_else(|| overflow::<T>("4 * distance"))?;
+        let Some(discriminant) = square.checked_sub(&quadruple) else {
+            return Ok(None);
         };
+        if !self.beats(&self.time.half()) {
+            return Ok(None);
+        }
+
+        // The discriminant is below `T*T`, so its root never exceeds the race time.
+        let mut first = self.time.checked_sub(&discriminant.isqrt()).unwrap_or_else(|| T::small(0)).half();
+        while let Some(earlier) = first.checked_sub(&one).filter(|earlier| self.beats(earlier)) {
+            first = earlier;
+        }
+        while !self.beats(&first) {
+            first = first.checked_add(&one).ok_or_else(|| overflow::<T>("hold time"))?;
+        }
+
+        let last = self.time.checked_sub(&first).ok_or_else(|| overflow::<T>("last hold time"))?;
+        let count = last
+            .checked_sub(&first)
+            .and_then(|span| span.checked_add(&one))
+            .ok_or_else(|| overflow::<T>("winning hold count"))?;
+        Ok(Some(WinningHolds { first, last, count }))
     }
-    pub fn from_str(lines: &str) -> RaceRecord {
-        let time_str = lines
-            .lines()
-            .nth(0)
-            .unwrap()
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .replace(" ", "");
-
-        let distance_str = lines
-            .lines()
-            .nth(1)
-            .unwrap()
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .replace(" ", "");
-//        println!("{}", time_str);
-//        println!("{}", distance_str);
-        let time: usize = time_str.parse().unwrap();
-        let distance: usize = distance_str.parse().unwrap();
 
-        RaceRecord { time, distance }
+    /// How many hold times beat the record.
+    pub fn ways_to_win(&self) -> Result<T, String> {
+        Ok(self.winning_holds()?.map_or_else(|| T::small(0), |holds| holds.count))
+    }
+}
+
+#[derive(Debug)]
+pub struct RaceRecords<T = usize> {
+    pub list: Vec<RaceRecord<T>>,
+}
+
+impl<T: RaceNumber> RaceRecords<T> {
+    /// Product of the ways to win every race, as part one wants it.
+    pub fn margin_of_error(&self) -> Result<T, String> {
+        let mut product = T::small(1);
+        for record in &self.list {
+            product = product.checked_mul(&record.ways_to_win()?).ok_or_else(|| overflow::<T>("margin of error"))?;
+        }
+        Ok(product)
+    }
+}
+
+/// One run of digits on the race sheet, with the 1-based position it was written at.
+#[derive(Debug, Clone, Copy)]
+struct Field<'a> {
+    digits: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl Field<'_> {
+    fn error(&self, message: String) -> ParseError {
+        ParseError { line: self.line, column: self.column, message }
+    }
+
+    fn parse<T: RaceNumber>(&self) -> Result<T, ParseError> {
+        self.digits.parse().map_err(|_| self.error(overflow::<T>(self.digits)))
     }
 }
+
+/// The race sheet as written: the digit groups of the `Time:` and `Distance:` rows, kept as
+/// text so they can be read as one race per column or kerned into a single race.
 #[derive(Debug)]
-struct RaceRecords {
-    list: Vec<RaceRecord>,
-}
-
-impl RaceRecords {
-    pub fn from_str(lines: &str) -> RaceRecords {
-        let times: Vec<usize> = lines
-            .lines()
-            .nth(0)
-            .unwrap()
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
-
-        let distances: Vec<usize> = lines
-            .lines()
-            .nth(1)
-            .unwrap()
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let records: Vec<RaceRecord> = times
-            .iter()
-            .zip(distances.iter())
-            .map(|(&time, &distance)| RaceRecord::from(time, distance))
-            .collect();
-
-        RaceRecords { list: records }
+pub struct RaceSheet<'a> {
+    times: Vec<Field<'a>>,
+    distances: Vec<Field<'a>>,
+}
+
+fn parse_row<'a>(text: &'a str, line: usize, label: &str) -> Result<Vec<Field<'a>>, ParseError> {
+    let mut cursor = Cursor::new(text.trim_end_matches('\r'), line);
+    cursor.expect(label)?;
+    cursor.expect(":")?;
+
+    let mut fields: Vec<Field> = vec![];
+    loop {
+        cursor.skip_spaces();
+        if cursor.is_at_end() {
+            return Ok(fields);
+        }
+        let column = cursor.error("").column;
+        let digits = cursor.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(cursor.error("expected a number"));
+        }
+        fields.push(Field { digits, line, column });
+    }
+}
+
+impl<'a> RaceSheet<'a> {
+    pub fn parse(text: &'a str) -> Result<RaceSheet<'a>, ParseError> {
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let missing = |line: usize, row: &str| ParseError { line, column: 1, message: format!("missing {} row", row) };
+
+        let (index, time_line) = lines.next().ok_or_else(|| missing(1, "Time:"))?;
+        let times = parse_row(time_line, index + 1, "Time")?;
+        let (index, distance_line) = lines.next().ok_or_else(|| missing(index + 2, "Distance:"))?;
+        let distances = parse_row(distance_line, index + 1, "Distance")?;
+        if let Some((index, _)) = lines.next() {
+            return Err(ParseError { line: index + 1, column: 1, message: "unexpected line after the Distance: row".to_string() });
+        }
+
+        if times.len() != distances.len() {
+            let (longer, shorter) = if times.len() > distances.len() { (&times, &distances) } else { (&distances, &times) };
+            return Err(longer[shorter.len()].error(format!(
+                "{} times but {} distances",
+                times.len(),
+                distances.len()
+            )));
+        }
+
+        Ok(RaceSheet { times, distances })
+    }
+
+    /// One race per column, as part one reads the sheet.
+    pub fn races<T: RaceNumber>(&self) -> Result<RaceRecords<T>, ParseError> {
+        let mut list: Vec<RaceRecord<T>> = vec![];
+        for (time, distance) in self.times.iter().zip(&self.distances) {
+            list.push(RaceRecord { time: time.parse()?, distance: distance.parse()? });
+        }
+        Ok(RaceRecords { list })
+    }
+
+    /// A single race with each row's digits run together, as part two reads the sheet.
+    pub fn kerned<T: RaceNumber>(&self) -> Result<RaceRecord<T>, ParseError> {
+        let kern = |fields: &[Field<'a>]| -> Result<T, ParseError> {
+            let first = fields.first().ok_or_else(|| ParseError { line: 1, column: 1, message: "no races".to_string() })?;
+            let digits: String = fields.iter().map(|field| field.digits).collect();
+            digits.parse().map_err(|_| first.error(overflow::<T>(&format!("kerned number {}", digits))))
+        };
+        Ok(RaceRecord { time: kern(&self.times)?, distance: kern(&self.distances)? })
     }
 }
 
@@ -118,21 +271,13 @@ pub fn solve_the_puzzle_6_1() {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(input) => {
-            let race_records: RaceRecords = RaceRecords::from_str(&input);
-            let mut number_of_way = 1;
-            for record in race_records.list {
-                let mut count = 0;
-                for i in 0..record.time {
-                    let r = i * (record.time-i);
-                    if r > record.distance {
-                        count+=1;
-                    }
-                }
-                number_of_way *= count;
-            }
-            println!("Number of way 1: {}", number_of_way);
-        }
+        Ok(input) => match RaceSheet::parse(&input)
+            .and_then(|sheet| sheet.races::<usize>())
+            .map_err(|error| error.to_string())
+            .and_then(|records| records.margin_of_error()) {
+            Ok(number_of_way) => println!("Number of way 1: {}", number_of_way),
+            Err(error) => println!("error {}", error),
+        },
     }
 }
 
@@ -141,18 +286,154 @@ pub fn solve_the_puzzle_6_2() {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(input) => {
-            let record: RaceRecord = RaceRecord::from_str(&input);
-            let mut total_ways = 0;
-            for i in 0..record.time {
-                let r = i * (record.time-i);
-                if r > record.distance {
-                    total_ways+=1;
+        Ok(input) => match RaceSheet::parse(&input)
+            .and_then(|sheet| sheet.kerned::<usize>())
+            .map_err(|error| error.to_string())
+            .and_then(|record| record.ways_to_win()) {
+            Ok(total_ways) => println!("Number of way 2: {}", total_ways),
+            Err(error) => println!("error {}", error),
+        },
+    }
+}
+
+fn print_winning_holds<T: RaceNumber>(record: &RaceRecord<T>) {
+    match record.winning_holds() {
+        Ok(Some(holds)) => println!(
+            "{:>16} {:>20} {:>16} {:>16} {:>16}",
+            record.time, record.distance, holds.first, holds.last, holds.count
+        ),
+        Ok(None) => println!("{:>16} {:>20} {:>16} {:>16} {:>16}", record.time, record.distance, "-", "-", 0),
+        Err(error) => println!("{:>16} {:>20} error {}", record.time, record.distance, error),
+    }
+}
+
+fn print_races<T: RaceNumber>(sheet: &RaceSheet) {
+    match sheet.races::<T>() {
+        Ok(records) => {
+            println!("{:>16} {:>20} {:>16} {:>16} {:>16}", "time", "distance", "first", "last", "ways");
+            for record in &records.list {
+                print_winning_holds(record);
+            }
+        }
+        Err(error) => println!("error {}", error),
+    }
+    println!("kerned:");
+    match sheet.kerned::<T>() {
+        Ok(record) => print_winning_holds(&record),
+        Err(error) => println!("error {}", error),
+    }
+}
+
+/// Prints the first and last winning hold time and how many there are, for every race and
+/// for the kerned single race: `day_6 [--precision usize|u128|big] [--input PATH]`.
+pub fn run(args: &[String]) {
+    let mut precision = "usize".to_string();
+    let parsed = parse::parse_args(args, "inputs/day_6_1.txt", |arg, args| {
+        match arg {
+            "--precision" => {
+                let name = args.value(arg, "usize, u128 or big")?;
+                if !["usize", "u128", "big"].contains(&name) {
+                    return Err("--precision needs usize, u128 or big".to_string());
                 }
+                precision = name.to_string();
             }
-            println!("Number of way 2: {}", total_ways);
+            _ => return Ok(false),
         }
+        Ok(true)
+    });
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            println!("error {}", error);
+            return;
+        }
+    };
+
+    match file::read_file(&input) {
+        Err(error) => {
+            println!("error {}", error)
+        }
+        Ok(text) => match RaceSheet::parse(&text) {
+            Err(error) => {
+                println!("error {}", error)
+            }
+            Ok(sheet) => match precision.as_str() {
+                "u128" => print_races::<u128>(&sheet),
+                "big" => print_races::<BigUint>(&sheet),
+                _ => print_races::<usize>(&sheet),
+            },
+        },
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(time: usize, distance: usize) -> RaceRecord {
+        RaceRecord { time, distance }
+    }
+
+    /// Every hold time tried one by one.
+    fn brute_force(time: usize, distance: usize) -> Option<WinningHolds> {
+        let wins: Vec<usize> = (0..=time).filter(|hold| hold * (time - hold) > distance).collect();
+        Some(WinningHolds { first: *wins.first()?, last: *wins.last()?, count: wins.len() })
+    }
+
+    #[test]
+    fn example_races() {
+        assert_eq!(record(7, 9).ways_to_win(), Ok(4));
+        assert_eq!(record(15, 40).ways_to_win(), Ok(8));
+        // 30^2 - 4 * 200 = 100 is a perfect square, so the roots 10 and 20 only tie the record.
+        assert_eq!(record(30, 200).winning_holds(), Ok(Some(WinningHolds { first: 11, last: 19, count: 9 })));
+        assert_eq!(record(71530, 940200).ways_to_win(), Ok(71503));
 
+        let sheet = RaceSheet::parse("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+        assert_eq!(sheet.races::<usize>().unwrap().margin_of_error(), Ok(288));
+        assert_eq!(sheet.kerned::<usize>().unwrap().ways_to_win(), Ok(71503));
+    }
+
+    #[test]
+    fn races_nobody_can_win() {
+        // A zero discriminant: holding for half the race only ties the record.
+        assert_eq!(record(4, 4).winning_holds(), Ok(None));
+        assert_eq!(record(10, 25).winning_holds(), Ok(None));
+        assert_eq!(record(0, 0).winning_holds(), Ok(None));
+        assert_eq!(record(1, 0).winning_holds(), Ok(None));
+        assert_eq!(record(2, 0).winning_holds(), Ok(Some(WinningHolds { first: 1, last: 1, count: 1 })));
+        assert_eq!(record(3, 100).winning_holds(), Ok(None));
+    }
+
+    #[test]
+    fn closed_form_matches_brute_force() {
+        for time in 0..80 {
+            for distance in 0..=time * time / 4 + 1 {
+                let expected = brute_force(time, distance);
+                assert_eq!(record(time, distance).winning_holds(), Ok(expected), "{} / {}", time, distance);
+            }
+        }
+    }
+
+    #[test]
+    fn every_precision_agrees() {
+        for (time, distance) in [(7u32, 9u32), (30, 200), (71530, 940200), (4, 4), (0, 0)] {
+            let expected = record(time as usize, distance as usize).ways_to_win().unwrap();
+            let wide = RaceRecord { time: time as u128, distance: distance as u128 };
+            assert_eq!(wide.ways_to_win(), Ok(expected as u128));
+            let big = RaceRecord { time: BigUint::from(time), distance: BigUint::from(distance) };
+            assert_eq!(big.ways_to_win(), Ok(BigUint::from(expected as u64)));
+        }
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        // 2^32 squared is past usize but fits u128; 2^64 squared only fits a big integer.
+        assert_eq!(record(1 << 32, 0).winning_holds(), Err("time * time overflows usize".to_string()));
+        let wide = RaceRecord { time: 1u128 << 32, distance: 0 };
+        assert_eq!(wide.ways_to_win(), Ok((1 << 32) - 1));
+        let wider = RaceRecord { time: 1u128 << 64, distance: 0 };
+        assert_eq!(wider.ways_to_win(), Err("time * time overflows u128".to_string()));
+        let big = RaceRecord { time: BigUint::from(1u128 << 64), distance: BigUint::zero() };
+        assert_eq!(big.ways_to_win(), Ok(BigUint::from(u64::MAX)));
+    }
+}
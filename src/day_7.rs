@@ -1,8 +1,9 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
+use crate::utils::parse::{self, Cursor, ParseError};
 
-use std::cmp::Ordering;
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::fmt;
 /*
 Your all-expenses-paid trip turns out to be a one-way, five-minute ride in an airship. (At least it's a cool airship!) It drops you off at the edge of a vast desert and descends back to Island Island.
 "Did you bring the parts?"
@@ -47,33 +48,204 @@ T55J5 and QQQJA are both three of a kind. QQQJA has a stronger first card, so it
 Now, you can determine the total winnings of this set of hands by adding up the result of multiplying each hand's bid with its rank (765 * 1 + 220 * 2 + 28 * 3 + 684 * 4 + 483 * 5).
 So the total winnings in this example are 6440.
 Find the rank of every hand in your set. What are the total winnings?
+--- Part Two ---
+To make things a little more interesting, the Elf introduces one additional rule. Now, J cards are jokers - wildcards that can act like whatever card would make the hand the strongest type possible.
+To balance this, J cards are now the weakest individual cards, weaker even than 2. The other cards stay in the same order: A, K, Q, T, 9, 8, 7, 6, 5, 4, 3, 2, J.
+J cards can pretend to be whatever card is best for the purpose of determining hand type; for example, QJJQ2 is now considered four of a kind.
+However, for the purpose of breaking ties between two hands of the same type, J is always treated as J, not the card it's pretending to be: JKKK2 is weaker than QQQQ2 because J is weaker than Q.
+Now, the above example goes very differently:
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+32T3K is still the only one pair; it doesn't contain any jokers, so its strength doesn't increase.
+KK677 is now the only two pair, making it the second-weakest hand.
+T55J5, KTJJT, and QQQJA are now all four of a kind! T55J5 gets rank 3, QQQJA gets rank 4, and KTJJT gets rank 5.
+With the new joker rule, the total winnings in this example are 5905.
+Using the new joker rule, find the rank of every hand in your set. What are the new total winnings?
 */
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
-enum Card {
-    A,
-    K,
-    Q,
-    J,
-    T,
-    Nine,
-    Eight,
-    Seven,
-    Six,
-    Five,
-    Four,
-    Three,
-    Two,
+/// How a game of Camel Cards is scored: which labels exist and how strong each one is, which
+/// of them are wildcards, and how hands of the same type are split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// Card labels from strongest to weakest.
+    pub card_order: &'static str,
+    /// Labels that act like whatever card makes the hand's type strongest.
+    pub wildcards: &'static str,
+    pub tie_break: TieBreak,
+    pub hand_size: usize,
+    pub ranking: Ranking,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
-    FiveOfAKind,
-    FourOfAKind,
-    FullHouse,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
-    HighCard,
+/// How hand types are ordered. A type is the shape of the hand: the sizes of its groups of
+/// matching cards, largest first, so a full house is `[3, 2]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ranking {
+    /// Shapes are compared group by group and the larger group wins. For five cards this is
+    /// the puzzle's order, from five of a kind `[5]` down to high card `[1, 1, 1, 1, 1]`.
+    BySize,
+    /// Shapes listed strongest first. Shapes the table leaves out rank below every listed
+    /// one, ordered among themselves as `BySize` would.
+    Table(&'static [&'static [usize]]),
+}
+
+/// Every way to split `n` cards into groups, largest group first, with the shapes themselves
+/// in descending order.
+fn partitions(n: usize, largest: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+
+    let mut shapes: Vec<Vec<usize>> = vec![];
+    for first in (1..=largest.min(n)).rev() {
+        for rest in partitions(n - first, first) {
+            let mut shape = vec![first];
+            shape.extend(rest);
+            shapes.push(shape);
+        }
+    }
+    shapes
+}
+
+/// How two hands of the same type are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Cards are compared in the order they were dealt and the first difference decides.
+    FirstDifference,
+    /// Cards are compared strongest first, as in poker.
+    HighestCards,
+}
+
+impl Rules {
+    /// Part one: J is a jack, between Q and T.
+    pub const STANDARD: Rules = Rules {
+        card_order: "AKQJT98765432",
+        wildcards: "",
+        tie_break: TieBreak::FirstDifference,
+        hand_size: 5,
+        ranking: Ranking::BySize,
+    };
+
+    /// Part two: J is a joker and the weakest card on its own.
+    pub const JOKERS: Rules = Rules {
+        card_order: "AKQT98765432J",
+        wildcards: "J",
+        tie_break: TieBreak::FirstDifference,
+        hand_size: 5,
+        ranking: Ranking::BySize,
+    };
+
+    fn card(&self, ch: char) -> Option<Card> {
+        self.card_order.chars().position(|label| label == ch).map(|rank| Card { rank, label: ch })
+    }
+
+    /// Every card these rules know, strongest first.
+    fn cards(&self) -> Vec<Card> {
+        self.card_order.chars().enumerate().map(|(rank, label)| Card { rank, label }).collect()
+    }
+
+    fn is_wildcard(&self, card: &Card) -> bool {
+        self.wildcards.contains(card.label)
+    }
+
+    /// The type ranking for hands of `hand_size` cards. Building it enumerates every shape,
+    /// so callers that rank many hands build it once.
+    fn types(&self) -> TypeTable {
+        TypeTable { by_size: partitions(self.hand_size, self.hand_size), ranking: self.ranking }
+    }
+
+    /// The cards in the order the tie-break compares them.
+    fn tie_break_order(&self, cards: &[Card]) -> Vec<Card> {
+        let mut cards = cards.to_vec();
+        if self.tie_break == TieBreak::HighestCards {
+            cards.sort();
+        }
+        cards
+    }
+
+    /// One number that orders hands exactly as these rules do, a larger key being a stronger
+    /// hand: the type's strength followed by one base-`card_order.len()` digit per card, in
+    /// the order the tie-break compares them. `None` if the hand is too long to fit in a `u128`.
+    fn sort_key(&self, types: &TypeTable, hand: &Hand) -> Option<u128> {
+        let base = self.card_order.len() as u128;
+        let type_strength = (types.len() - types.rank(&evaluate_hand(self, hand))) as u128;
+
+        self.tie_break_order(&hand.cards)
+            .iter()
+            .try_fold(type_strength, |key, card| key.checked_mul(base)?.checked_add(base - 1 - card.rank as u128))
+    }
+
+    /// The same ordering as `sort_key` without packing it: the type's rank, then the card
+    /// ranks in tie-break order, each reversed so a larger key is still a stronger hand.
+    fn unpacked_sort_key(&self, types: &TypeTable, hand: &Hand) -> (Reverse<usize>, Vec<Reverse<usize>>) {
+        let cards = self.tie_break_order(&hand.cards).iter().map(|card| Reverse(card.rank)).collect();
+        (Reverse(types.rank(&evaluate_hand(self, hand))), cards)
+    }
+}
+
+/// Every shape a hand can have, in the order the rules rank them.
+struct TypeTable {
+    /// All shapes of `hand_size` cards, strongest first by size.
+    by_size: Vec<Vec<usize>>,
+    ranking: Ranking,
+}
+
+impl TypeTable {
+    /// Where `hand_type` stands among the types; 0 is the strongest.
+    fn rank(&self, hand_type: &HandType) -> usize {
+        let by_size_rank = self
+            .by_size
+            .iter()
+            .position(|shape| *shape == hand_type.shape)
+            .unwrap_or(self.by_size.len());
+
+        match self.ranking {
+            Ranking::BySize => by_size_rank,
+            Ranking::Table(table) => match table.iter().position(|&shape| shape == hand_type.shape.as_slice()) {
+                Some(rank) => rank,
+                None => table.len() + by_size_rank,
+            },
+        }
+    }
+
+    /// One more than the largest rank, even for shapes no ranking lists.
+    fn len(&self) -> usize {
+        let table_len = match self.ranking {
+            Ranking::BySize => 0,
+            Ranking::Table(table) => table.len(),
+        };
+        table_len + self.by_size.len()
+    }
+}
+
+/// A card and its place in the rules' card order; rank 0 is the strongest card.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+struct Card {
+    rank: usize,
+    label: char,
+}
+
+/// A hand's shape once the wildcards have joined its largest group; `TypeTable::rank` says
+/// how strong it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct HandType {
+    shape: Vec<usize>,
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.shape.as_slice() {
+            [5] => write!(f, "five of a kind"),
+            [4, 1] => write!(f, "four of a kind"),
+            [3, 2] => write!(f, "full house"),
+            [3, 1, 1] => write!(f, "three of a kind"),
+            [2, 2, 1] => write!(f, "two pair"),
+            [2, 1, 1, 1] => write!(f, "one pair"),
+            [1, 1, 1, 1, 1] => write!(f, "high card"),
+            shape => write!(f, "{:?}", shape),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -82,120 +254,293 @@ struct Hand {
     bid: usize,
 }
 
-fn parse_card(ch: char) -> Card {
-    match ch {
-        'A' => Card::A,
-        'K' => Card::K,
-        'Q' => Card::Q,
-        'J' => Card::J,
-        'T' => Card::T,
-        '9' => Card::Nine,
-        '8' => Card::Eight,
-        '7' => Card::Seven,
-        '6' => Card::Six,
-        '5' => Card::Five,
-        '4' => Card::Four,
-        '3' => Card::Three,
-        '2' => Card::Two,
-        _ => panic!("Invalid card label"),
+fn parse_card(rules: &Rules, cursor: &mut Cursor) -> Result<Card, ParseError> {
+    match cursor.peek().and_then(|ch| rules.card(ch)) {
+        Some(card) => {
+            cursor.eat(card.label);
+            Ok(card)
+        }
+        None => Err(cursor.error(format!("expected a card, one of {}", rules.card_order))),
     }
 }
 
-fn parse_hand(s: &str) -> Hand {
-    let mut iter = s.chars();
-    let cards: Vec<Card> = (0..5).map(|_| parse_card(iter.next().unwrap())).collect();
-    iter.next();
-    let bid: usize = iter.collect::<String>().parse().unwrap();
-    Hand { cards, bid }
+/// A line like `32T3K 765`: exactly `rules.hand_size` cards, then the bid.
+fn parse_hand(rules: &Rules, text: &str, line: usize) -> Result<Hand, ParseError> {
+    let mut cursor = Cursor::new(text, line);
+    let mut cards: Vec<Card> = vec![];
+    while cursor.peek().is_some_and(|ch| !ch.is_whitespace()) {
+        cards.push(parse_card(rules, &mut cursor)?);
+    }
+    if cards.len() != rules.hand_size {
+        return Err(cursor.error(format!("hand has {} cards, expected {}", cards.len(), rules.hand_size)));
+    }
+
+    cursor.skip_spaces();
+    let bid = cursor.number()?;
+    cursor.skip_spaces();
+    if !cursor.is_at_end() {
+        return Err(cursor.error("expected the end of the line after the bid"));
+    }
+    Ok(Hand { cards, bid })
+}
+
+/// One hand per line; blank lines are skipped.
+fn parse_hands(rules: &Rules, text: &str) -> Result<Vec<Hand>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_hand(rules, line.trim_end(), index + 1))
+        .collect()
 }
 
 fn count_card_occurrences(cards: &[Card]) -> HashMap<Card, usize> {
     let mut occurrences = HashMap::new();
     for card in cards {
-        *occurrences.entry(card.clone()).or_insert(0) += 1;
+        *occurrences.entry(*card).or_insert(0) += 1;
     }
     occurrences
 }
 
-fn evaluate_hand(hand: &Hand) -> (HandType, Vec<Card>) {
-    let mut cards = hand.cards.clone();
-    let occurrences = count_card_occurrences(&hand.cards);
+/// Groups the ordinary cards by label and lets every wildcard join the largest group, which
+/// always makes the strongest type; a hand of nothing but wildcards is a single group.
+fn classify(rules: &Rules, cards: &[Card]) -> HandType {
+    let ordinary: Vec<Card> = cards.iter().filter(|card| !rules.is_wildcard(card)).copied().collect();
+    let wildcards = cards.len() - ordinary.len();
 
-    // Check for Five of a Kind
-    if occurrences.values().any(|&count| count == 5) {
-        return (HandType::FiveOfAKind, cards);
+    let mut shape: Vec<usize> = count_card_occurrences(&ordinary).into_values().collect();
+    shape.sort_unstable_by(|a, b| b.cmp(a));
+    match shape.first_mut() {
+        Some(largest) => *largest += wildcards,
+        None => shape.push(wildcards),
     }
 
-    // Check for Four of a Kind
-    if occurrences.values().any(|&count| count == 4) {
-        return (HandType::FourOfAKind, cards);
-    }
+    HandType { shape }
+}
+
+fn evaluate_hand(rules: &Rules, hand: &Hand) -> HandType {
+    classify(rules, &hand.cards)
+}
 
-    // Check for Full House
-    if occurrences.values().any(|&count| count == 3) && occurrences.values().any(|&count| count == 2) {
-        return (HandType::FullHouse, cards);
+/// The cards as the wildcards play them: each becomes the label of the largest ordinary group,
+/// the stronger label when groups tie, which is the substitution `classify` assumes.
+fn played_as(rules: &Rules, cards: &[Card]) -> Vec<Card> {
+    let ordinary: Vec<Card> = cards.iter().filter(|card| !rules.is_wildcard(card)).copied().collect();
+    let target = count_card_occurrences(&ordinary)
+        .into_iter()
+        .max_by_key(|&(card, count)| (count, Reverse(card.rank)))
+        .map(|(card, _)| card)
+        .or_else(|| rules.cards().into_iter().find(|card| !rules.is_wildcard(card)));
+
+    match target {
+        Some(target) => cards.iter().map(|card| if rules.is_wildcard(card) { target } else { *card }).collect(),
+        None => cards.to_vec(),
     }
+}
 
-    // Check for Three of a Kind
-    if occurrences.values().any(|&count| count == 3) {
-        return (HandType::ThreeOfAKind, cards);
+/// Calls `visit` with every multiset of `size` cards drawn from `labels` labels, as label
+/// indices in ascending order. Hand types ignore card order, so this covers every type a
+/// hand can have in far fewer steps than every ordering would.
+fn each_multiset(labels: usize, size: usize, mut visit: impl FnMut(&[usize])) {
+    if labels == 0 {
+        return;
     }
 
-    // Check for Two Pair
-    if occurrences.values().filter(|&&count| count == 2).count() == 2 {
-        return (HandType::TwoPair, cards);
+    let mut choice = vec![0; size];
+    loop {
+        visit(&choice);
+
+        let Some(digit) = choice.iter().rposition(|&label| label + 1 < labels) else {
+            break;
+        };
+        let next = choice[digit] + 1;
+        choice[digit..].fill(next);
     }
+}
 
-    // Check for One Pair
-    if occurrences.values().any(|&count| count == 2) {
-        return (HandType::OnePair, cards);
+/// The strongest type reachable by trying every ordinary label in place of every wildcard,
+/// with the substitution that reaches it (stronger labels win ties). Which wildcard gets
+/// which label cannot change the type, so each multiset of labels is tried once. Still
+/// exponential in the number of wildcards; it is the reference `classify` is checked against.
+fn best_substitution(rules: &Rules, types: &TypeTable, cards: &[Card]) -> (HandType, Vec<Card>) {
+    let labels: Vec<Card> = rules.cards().into_iter().filter(|card| !rules.is_wildcard(card)).collect();
+    let wild: Vec<usize> = (0..cards.len()).filter(|&index| rules.is_wildcard(&cards[index])).collect();
+    if labels.is_empty() || wild.is_empty() {
+        return (classify(rules, cards), cards.to_vec());
     }
 
-    // High Card
-    (HandType::HighCard, cards)
+    let mut best: Option<(HandType, Vec<Card>)> = None;
+    each_multiset(labels.len(), wild.len(), |choice| {
+        let mut candidate = cards.to_vec();
+        for (&index, &label) in wild.iter().zip(choice) {
+            candidate[index] = labels[label];
+        }
+        let hand_type = classify(rules, &candidate);
+        if best.as_ref().is_none_or(|(best_type, _)| types.rank(&hand_type) < types.rank(best_type)) {
+            best = Some((hand_type, candidate));
+        }
+    });
+
+    best.unwrap()
 }
 
-fn compare_hands(hand1: &Hand, hand2: &Hand) -> Ordering {
-    let (type1, cards1) = evaluate_hand(hand1);
-    let (type2, cards2) = evaluate_hand(hand2);
+/// Classifies every multiset of cards both ways and returns how many were checked and the
+/// hands where the two disagree.
+fn check_classifier(rules: &Rules) -> (usize, Vec<String>) {
+    let labels: Vec<Card> = rules.cards();
+    let types = rules.types();
+    let mut checked = 0;
+    let mut mismatches: Vec<String> = vec![];
 
-    if type1 != type2 {
-        return type1.cmp(&type2);
-    }
-    // If hand types are the same, compare individual card values
-    for (card1, card2) in cards1.iter().zip(cards2.iter()) {
-        let cmp = card1.cmp(card2);
-        if cmp != Ordering::Equal {
-            return cmp;
+    each_multiset(labels.len(), rules.hand_size, |choice| {
+        let cards: Vec<Card> = choice.iter().map(|&label| labels[label]).collect();
+        let fast = classify(rules, &cards);
+        let (slow, substitution) = best_substitution(rules, &types, &cards);
+        if fast != slow {
+            let text = |cards: &[Card]| cards.iter().map(|card| card.label).collect::<String>();
+            mismatches.push(format!("{}: {}, but {} makes {}", text(&cards), fast, text(&substitution), slow));
         }
-    }
+        checked += 1;
+    });
 
-    // If all cards are equal, return Equal
-    Ordering::Equal
+    (checked, mismatches)
 }
 
-fn calculate_total_winnings(mut hands: Vec<Hand>) -> usize {
-    let l = hands.len();
-    hands.sort_by(|a, b| compare_hands(&a, &b));
-    let hands_with_rank: Vec<(Hand, usize)> = hands.into_iter().enumerate().map(|(rank, hand)| (hand, l - rank)).collect();
-    //println!("{:?}", hands_with_rank);
-    hands_with_rank
+/// The hands from weakest to strongest. The weakest hand gets rank 1 and the strongest
+/// `hands.len()`, so each hand's rank is its position plus one. Hands too long for a packed
+/// key are sorted by their unpacked keys instead.
+fn rank_hands(rules: &Rules, mut hands: Vec<Hand>) -> Vec<Hand> {
+    let types = rules.types();
+    match hands.iter().map(|hand| rules.sort_key(&types, hand)).collect::<Option<Vec<u128>>>() {
+        Some(keys) => {
+            let mut keyed: Vec<(u128, Hand)> = keys.into_iter().zip(hands).collect();
+            keyed.sort_by_key(|(key, _)| *key);
+            keyed.into_iter().map(|(_, hand)| hand).collect()
+        }
+        None => {
+            hands.sort_by_cached_key(|hand| rules.unpacked_sort_key(&types, hand));
+            hands
+        }
+    }
+}
+
+fn calculate_total_winnings(rules: &Rules, hands: Vec<Hand>) -> usize {
+    rank_hands(rules, hands)
         .iter()
-        .map(|(hand, rank)| hand.bid * rank)
+        .enumerate()
+        .map(|(index, hand)| hand.bid * (index + 1))
         .sum()
 }
 
+fn labels(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.label).collect()
+}
+
+/// One hand of the ranking report, weakest first.
+struct RankingLine {
+    rank: usize,
+    cards: Vec<Card>,
+    bid: usize,
+    contribution: usize,
+    /// The type with every card taken at face value.
+    plain_type: HandType,
+    hand_type: HandType,
+    /// The cards with each wildcard replaced by the label that makes `hand_type`, if any
+    /// wildcard was played.
+    substitution: Option<Vec<Card>>,
+}
+
+fn explain_ranking(rules: &Rules, hands: Vec<Hand>) -> Vec<RankingLine> {
+    let face_value = Rules { wildcards: "", ..*rules };
+
+    rank_hands(rules, hands)
+        .into_iter()
+        .enumerate()
+        .map(|(index, hand)| {
+            let substitution = played_as(rules, &hand.cards);
+            RankingLine {
+                rank: index + 1,
+                bid: hand.bid,
+                contribution: hand.bid * (index + 1),
+                plain_type: classify(&face_value, &hand.cards),
+                hand_type: classify(rules, &hand.cards),
+                substitution: Some(substitution).filter(|substituted| *substituted != hand.cards),
+                cards: hand.cards,
+            }
+        })
+        .collect()
+}
+
+fn render_ranking_table(lines: &[RankingLine]) -> String {
+    let mut out = format!(
+        "{:>6} {:<8} {:>6} {:>12}  {:<16} {:<16} {}\n",
+        "rank", "cards", "bid", "winnings", "face value", "with wildcards", "played as"
+    );
+    for line in lines {
+        out.push_str(&format!(
+            "{:>6} {:<8} {:>6} {:>12}  {:<16} {:<16} {}\n",
+            line.rank,
+            labels(&line.cards),
+            line.bid,
+            line.contribution,
+            line.plain_type.to_string(),
+            line.hand_type.to_string(),
+            line.substitution.as_deref().map_or("-".to_string(), labels)
+        ));
+    }
+    out
+}
+
+/// Every pair of neighbouring ranks whose hands share a type, so the tie-break ordered them,
+/// with the card that decided it.
+fn render_tie_breaks(rules: &Rules, lines: &[RankingLine]) -> String {
+    let mut out = String::new();
+    for pair in lines.windows(2) {
+        let (weaker, stronger) = (&pair[0], &pair[1]);
+        if weaker.hand_type != stronger.hand_type {
+            continue;
+        }
+
+        let weaker_order = rules.tie_break_order(&weaker.cards);
+        let stronger_order = rules.tie_break_order(&stronger.cards);
+        let decided = weaker_order.iter().zip(&stronger_order).position(|(a, b)| a != b);
+        let position = match (rules.tie_break, decided) {
+            (_, None) => "nothing, the hands are identical".to_string(),
+            (TieBreak::FirstDifference, Some(index)) => format!(
+                "card {} ({} < {})",
+                index + 1,
+                weaker_order[index].label,
+                stronger_order[index].label
+            ),
+            (TieBreak::HighestCards, Some(index)) => format!(
+                "highest card {} ({} < {})",
+                index + 1,
+                weaker_order[index].label,
+                stronger_order[index].label
+            ),
+        };
+        out.push_str(&format!(
+            "rank {} {} < rank {} {}: both {}, decided by {}\n",
+            weaker.rank,
+            labels(&weaker.cards),
+            stronger.rank,
+            labels(&stronger.cards),
+            weaker.hand_type,
+            position
+        ));
+    }
+    out
+}
+
 
 pub fn solve_the_puzzle_7_1() {
     match file::read_file("inputs/day_7_1.txt") {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(input) => {
-            let hands: Vec<Hand> = input.lines().map(|s| parse_hand(s)).collect();
-            let total_winnings = calculate_total_winnings(hands);
-            println!("Total Winnings: {}", total_winnings);
-        }
+        Ok(input) => match parse_hands(&Rules::STANDARD, &input) {
+            Ok(hands) => println!("Total Winnings: {}", calculate_total_winnings(&Rules::STANDARD, hands)),
+            Err(error) => println!("error {}", error),
+        },
     }
 }
 
@@ -204,10 +549,248 @@ pub fn solve_the_puzzle_7_2() {
         Err(error) => {
             println!("error {}", error)
         }
-        Ok(input) => {
+        Ok(input) => match parse_hands(&Rules::JOKERS, &input) {
+            Ok(hands) => println!("Total Winnings Part 2: {}", calculate_total_winnings(&Rules::JOKERS, hands)),
+            Err(error) => println!("error {}", error),
+        },
+    }
+}
+
+/// Ranking lists every shape a hand can have, and past this there are tens of thousands.
+const MAX_HAND_SIZE: usize = 40;
+
+/// Beyond this the brute-force side of `check-jokers` takes too long to be useful.
+const MAX_CHECKED_HAND_SIZE: usize = 6;
+
+#[derive(PartialEq)]
+enum Output {
+    Totals,
+    Report,
+    Ties,
+    CheckJokers,
+}
+
+/// Prints the total winnings under one or both rule presets, explains the final ordering
+/// hand by hand (or only the neighbouring ranks a tie-break decided), or checks the joker
+/// classifier against brute-force substitution on every possible hand:
+/// `day_7 [report [--ties] | check-jokers] [--rules standard|jokers] [--hand-size N] [--input PATH]`.
+pub fn run(args: &[String]) {
+    let mut presets = vec![("standard", Rules::STANDARD), ("jokers", Rules::JOKERS)];
+    let mut output = Output::Totals;
+    let mut hand_size = 5;
+    let parsed = parse::parse_args(args, "inputs/day_7_1.txt", |arg, args| {
+        match arg {
+            "--rules" => match args.value(arg, "standard or jokers")? {
+                "standard" => presets = vec![("standard", Rules::STANDARD)],
+                "jokers" => presets = vec![("jokers", Rules::JOKERS)],
+                _ => return Err("--rules needs standard or jokers".to_string()),
+            },
+            "--hand-size" => {
+                hand_size = args.parsed(arg, "a positive number")?;
+                if hand_size == 0 || hand_size > MAX_HAND_SIZE {
+                    return Err(format!("--hand-size needs a number from 1 to {}", MAX_HAND_SIZE));
+                }
+            }
+            "check-jokers" => output = Output::CheckJokers,
+            "report" => output = Output::Report,
+            "--ties" if output == Output::Report => output = Output::Ties,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    });
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            println!("error {}", error);
+            return;
+        }
+    };
+
+    for (_, rules) in presets.iter_mut() {
+        rules.hand_size = hand_size;
+    }
 
+    if output == Output::CheckJokers {
+        if hand_size > MAX_CHECKED_HAND_SIZE {
+            println!("error check-jokers handles hands of at most {} cards", MAX_CHECKED_HAND_SIZE);
+            return;
+        }
+        for (name, rules) in &presets {
+            let (checked, mismatches) = check_classifier(rules);
+            println!("{}: {} card multisets checked, {} mismatches", name, checked, mismatches.len());
+            for mismatch in &mismatches {
+                println!("  {}", mismatch);
+            }
+        }
+        return;
+    }
+
+    match file::read_file(&input) {
+        Err(error) => {
+            println!("error {}", error)
+        }
+        Ok(text) => {
+            for (name, rules) in &presets {
+                let hands = match parse_hands(rules, &text) {
+                    Ok(hands) => hands,
+                    Err(error) => {
+                        println!("error {}", error);
+                        return;
+                    }
+                };
+                match output {
+                    Output::Report => {
+                        println!("{}:", name);
+                        print!("{}", render_ranking_table(&explain_ranking(rules, hands)));
+                    }
+                    Output::Ties => {
+                        println!("{}:", name);
+                        print!("{}", render_tie_breaks(rules, &explain_ranking(rules, hands)));
+                    }
+                    _ => println!("Total winnings ({}): {}", name, calculate_total_winnings(rules, hands)),
+                }
+            }
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    const EXAMPLE: &str = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n";
+
+    fn cards(rules: &Rules, labels: &str) -> Vec<Card> {
+        labels.chars().map(|ch| rules.card(ch).unwrap()).collect()
+    }
+
+    fn hand(rules: &Rules, labels: &str) -> Hand {
+        Hand { cards: cards(rules, labels), bid: 0 }
+    }
+
+    fn stronger(rules: &Rules, a: &str, b: &str) -> bool {
+        let types = rules.types();
+        rules.sort_key(&types, &hand(rules, a)).unwrap() > rules.sort_key(&types, &hand(rules, b)).unwrap()
+    }
+
+    /// The comparison `sort_key` packs into one number: type first, then the cards one by one
+    /// in tie-break order. `Greater` means `a` is the stronger hand.
+    fn compare_by_type_then_cards(rules: &Rules, types: &TypeTable, a: &Hand, b: &Hand) -> Ordering {
+        let strengths = |hand: &Hand| -> Vec<Reverse<usize>> {
+            rules.tie_break_order(&hand.cards).iter().map(|card| Reverse(card.rank)).collect()
+        };
+        types
+            .rank(&evaluate_hand(rules, b))
+            .cmp(&types.rank(&evaluate_hand(rules, a)))
+            .then_with(|| strengths(a).cmp(&strengths(b)))
+    }
+
+    /// `count` hands drawn from a fixed linear congruential sequence, so every run checks the
+    /// same hands. Only every third label is used, so equal types and shared leading cards
+    /// come up often.
+    fn sample_hands(rules: &Rules, count: usize) -> Vec<Hand> {
+        let deck = rules.cards();
+        let mut state: u64 = 7;
+        let mut next = |bound: usize| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as usize % bound
+        };
+        (0..count)
+            .map(|_| Hand { cards: (0..rules.hand_size).map(|_| deck[next(5) * 3]).collect(), bid: 0 })
+            .collect()
+    }
+
+    #[test]
+    fn example_totals() {
+        let standard = parse_hands(&Rules::STANDARD, EXAMPLE).unwrap();
+        assert_eq!(calculate_total_winnings(&Rules::STANDARD, standard), 6440);
+        let jokers = parse_hands(&Rules::JOKERS, EXAMPLE).unwrap();
+        assert_eq!(calculate_total_winnings(&Rules::JOKERS, jokers), 5905);
+    }
+
+    #[test]
+    fn same_type_hands_are_split_by_the_first_different_card() {
+        assert!(stronger(&Rules::STANDARD, "33332", "2AAAA"));
+        assert!(stronger(&Rules::STANDARD, "77888", "77788"));
+    }
+
+    #[test]
+    fn jokers_break_ties_as_the_weakest_card() {
+        assert!(stronger(&Rules::JOKERS, "QQQQ2", "JKKK2"));
+        assert_eq!(classify(&Rules::JOKERS, &cards(&Rules::JOKERS, "JKKK2")).shape, vec![4, 1]);
+    }
+
+    #[test]
+    fn sort_key_agrees_with_comparing_type_then_cards() {
+        const TABLE: &[&[usize]] = &[&[3, 1, 1], &[2, 2, 1]];
+        let variants = [
+            Rules::STANDARD,
+            Rules::JOKERS,
+            Rules { tie_break: TieBreak::HighestCards, ..Rules::JOKERS },
+            Rules { ranking: Ranking::Table(TABLE), ..Rules::STANDARD },
+        ];
+
+        for rules in variants {
+            let types = rules.types();
+            let hands = sample_hands(&rules, 200);
+            for a in &hands {
+                for b in &hands {
+                    let by_key = rules.sort_key(&types, a).unwrap().cmp(&rules.sort_key(&types, b).unwrap());
+                    let expected = compare_by_type_then_cards(&rules, &types, a, b);
+                    assert_eq!(by_key, expected, "{} vs {}", labels(&a.cards), labels(&b.cards));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn classifier_matches_brute_force_for_every_five_card_hand() {
+        let (checked, mismatches) = check_classifier(&Rules::JOKERS);
+        // Multisets of 5 from 13 labels: C(17, 5).
+        assert_eq!(checked, 6188);
+        assert_eq!(mismatches, Vec::<String>::new());
+    }
+
+    #[test]
+    fn classifier_matches_brute_force_for_three_card_hands() {
+        for rules in [Rules::STANDARD, Rules::JOKERS] {
+            let rules = Rules { hand_size: 3, ..rules };
+            let (checked, mismatches) = check_classifier(&rules);
+            assert_eq!(checked, 455);
+            assert_eq!(mismatches, Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn all_and_nearly_all_jokers_make_five_of_a_kind() {
+        let rules = Rules::JOKERS;
+        for hand in ["JJJJJ", "JJJJA", "JJJJ2", "2JJJJ", "JJKJJ"] {
+            let cards = cards(&rules, hand);
+            assert_eq!(classify(&rules, &cards).shape, vec![5], "{}", hand);
+            assert_eq!(best_substitution(&rules, &rules.types(), &cards).0.shape, vec![5], "{}", hand);
+        }
+    }
+
+    #[test]
+    fn jokers_in_a_full_house_join_the_larger_group() {
+        let rules = Rules::JOKERS;
+        assert_eq!(classify(&rules, &cards(&rules, "JJKKK")).shape, vec![5]);
+        assert_eq!(classify(&rules, &cards(&rules, "JJJKK")).shape, vec![5]);
+        assert_eq!(classify(&Rules::STANDARD, &cards(&Rules::STANDARD, "JJKKK")).shape, vec![3, 2]);
+    }
 
+    #[test]
+    fn hands_too_long_for_a_packed_key_are_still_ranked() {
+        let rules = Rules { hand_size: 33, ..Rules::JOKERS };
+        let types = rules.types();
+        let hands = sample_hands(&rules, 100);
+        assert!(hands.iter().any(|hand| rules.sort_key(&types, hand).is_none()));
+
+        let ranked = rank_hands(&rules, hands);
+        assert_eq!(ranked.len(), 100);
+        for pair in ranked.windows(2) {
+            assert_ne!(compare_by_type_then_cards(&rules, &types, &pair[0], &pair[1]), Ordering::Greater);
+        }
+    }
+}
@@ -1,5 +1,5 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
+use crate::utils::number;
 
 use std::collections::HashMap;
 
@@ -141,44 +141,13 @@ pub fn solve_the_puzzle_8_2() {
                 let (key, node) = parse(pair);
                 graph.insert(key.to_string(), node);
             }
-            let result = graph.keys()
+            let cycles = graph.keys()
                 .filter(|k| k.ends_with("A"))
-                .map(|node| steps(&instructions, &graph, node.to_string(), "Z".to_string()))
-                .fold(1, |ans, x| (x*ans)/gcd(x, ans));
-            println!("{}", result);
-        }
-    }
-}
-
-fn gcd(a: usize, b: usize) -> usize {
-    // Base cases
-    if b == 0 {
-        return a;
-    }
-
-    if a == 0 {
-        return b;
-    }
-
-    // Find the greatest power of 2 that divides both a and b
-    let shift = (a | b).trailing_zeros();
-
-    // Divide both a and b by 2 until their greatest power of 2 is different
-    let mut a = a >> a.trailing_zeros();
-    let mut b = b >> b.trailing_zeros();
-
-    while a != b {
-        if a > b {
-            a -= b;
-            a >>= a.trailing_zeros();
-        } else {
-            b -= a;
-            b >>= b.trailing_zeros();
+                .map(|node| steps(&instructions, &graph, node.to_string(), "Z".to_string()));
+            match number::lcm_of(cycles) {
+                Some(result) => println!("{}", result),
+                None => println!("error step count overflows usize"),
+            }
         }
     }
-
-    // Multiply the result by 2^shift to get the final GCD
-    a << shift
 }
-
-
@@ -1,5 +1,4 @@
-#[path = "utils/file.rs"]
-mod file;
+use crate::utils::file;
 
 pub fn solve_the_puzzle_9_1() {
     match file::read_file("inputs/day_9_1.txt") {
@@ -0,0 +1,378 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul, Shl, Shr, Sub};
+use std::str::FromStr;
+
+/// An unsigned integer of any size, stored as little-endian 32-bit limbs with no trailing
+/// zero limbs, so zero is the empty vector and equal values have equal limbs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { limbs: vec![] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> BigUint {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    /// Number of significant bits; zero has none.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+        }
+    }
+
+    /// `self - rhs`, or `None` if that would go below zero.
+    pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+        if *self < *rhs {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (index, &limb) in self.limbs.iter().enumerate() {
+            let mut difference = limb as i64 - rhs.limbs.get(index).copied().unwrap_or(0) as i64 - borrow;
+            borrow = 0;
+            if difference < 0 {
+                difference += 1 << 32;
+                borrow = 1;
+            }
+            limbs.push(difference as u32);
+        }
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    fn mul_small(&self, factor: u32) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0u64;
+        for &limb in &self.limbs {
+            let product = limb as u64 * factor as u64 + carry;
+            limbs.push(product as u32);
+            carry = product >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (index, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << 32) | limb as u64;
+            limbs[index] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (BigUint::from_limbs(limbs), remainder as u32)
+    }
+
+    /// Largest `r` with `r * r <= self`, worked out bit by bit so no division is needed.
+    pub fn isqrt(&self) -> BigUint {
+        let mut rest = self.clone();
+        let mut root = BigUint::zero();
+        if self.is_zero() {
+            return root;
+        }
+
+        // The highest power of four not above `self`.
+        let mut bit = &BigUint::from(1u32) << ((self.bits() - 1) & !1);
+        while !bit.is_zero() {
+            let candidate = &root + &bit;
+            if rest >= candidate {
+                rest = &rest - &candidate;
+                root = &(&root >> 1) + &bit;
+            } else {
+                root = &root >> 1;
+            }
+            bit = &bit >> 2;
+        }
+        root
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> BigUint {
+        BigUint::from_limbs(vec![value])
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> BigUint {
+        BigUint::from_limbs(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> BigUint {
+        BigUint::from_limbs((0..4).map(|index| (value >> (32 * index)) as u32).collect())
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len()).then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let length = self.limbs.len().max(rhs.limbs.len());
+        let mut limbs = Vec::with_capacity(length + 1);
+        let mut carry = 0u64;
+        for index in 0..length {
+            let sum = self.limbs.get(index).copied().unwrap_or(0) as u64
+                + rhs.limbs.get(index).copied().unwrap_or(0) as u64
+                + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+/// Panics below zero, like primitive subtraction in debug builds; see `checked_sub`.
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("BigUint subtraction went below zero")
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let current = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        let (whole, part) = (bits / 32, bits % 32);
+        let mut limbs = vec![0u32; whole];
+        let mut carry = 0u32;
+        for &limb in &self.limbs {
+            limbs.push(if part == 0 { limb } else { (limb << part) | carry });
+            carry = if part == 0 { 0 } else { limb >> (32 - part) };
+        }
+        limbs.push(carry);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        let (whole, part) = (bits / 32, bits % 32);
+        let kept = self.limbs.get(whole..).unwrap_or(&[]);
+        let limbs = (0..kept.len())
+            .map(|index| {
+                let high = if part == 0 { 0 } else { kept.get(index + 1).map_or(0, |&next| next << (32 - part)) };
+                (kept[index] >> part) | high
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+}
+
+/// A string that is not a plain run of decimal digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigUintError {
+    pub text: String,
+}
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} is not an unsigned decimal number", self.text)
+    }
+}
+
+impl Error for ParseBigUintError {}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(text: &str) -> Result<BigUint, ParseBigUintError> {
+        if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigUintError { text: text.to_string() });
+        }
+
+        let mut value = BigUint::zero();
+        for digit in text.bytes() {
+            value = &value.mul_small(10) + &BigUint::from((digit - b'0') as u32);
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad("0");
+        }
+
+        // Peel off nine decimal digits at a time, lowest first.
+        let mut chunks: Vec<u32> = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem_small(1_000_000_000);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+
+        let mut text = chunks.pop().map_or(String::new(), |top| top.to_string());
+        for chunk in chunks.iter().rev() {
+            text.push_str(&format!("{:09}", chunk));
+        }
+        f.pad(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(value: u128) -> BigUint {
+        BigUint::from(value)
+    }
+
+    /// Values around the limb boundaries, plus a fixed spread of larger ones.
+    fn samples() -> Vec<u128> {
+        let mut values = vec![0, 1, 2, 3, 9, 10, 999_999_999, 1_000_000_000];
+        for bits in [31, 32, 33, 63, 64, 65, 95, 96, 97] {
+            let power = 1u128 << bits;
+            values.extend([power - 1, power, power + 1]);
+        }
+        let mut state: u64 = 11;
+        for _ in 0..40 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            values.push((state as u128) << (state % 60));
+        }
+        values
+    }
+
+    #[test]
+    fn zero_has_no_limbs() {
+        assert!(BigUint::zero().is_zero());
+        assert_eq!(big(0), BigUint::zero());
+        assert_eq!(BigUint::from(0u64), BigUint::zero());
+        assert_eq!(big(0).bits(), 0);
+        assert_eq!(big(1 << 32).bits(), 33);
+        assert_eq!(big(u128::MAX).bits(), 128);
+    }
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        let values = samples();
+        for &a in &values {
+            for &b in &values {
+                if let Some(sum) = a.checked_add(b) {
+                    assert_eq!(&big(a) + &big(b), big(sum), "{} + {}", a, b);
+                }
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(&big(a) * &big(b), big(product), "{} * {}", a, b);
+                }
+                assert_eq!(big(a).checked_sub(&big(b)), a.checked_sub(b).map(big), "{} - {}", a, b);
+                assert_eq!(big(a).cmp(&big(b)), a.cmp(&b), "{} cmp {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn carries_cross_limb_boundaries() {
+        assert_eq!(&big(u32::MAX as u128) + &big(1), big(1 << 32));
+        assert_eq!(&big(u64::MAX as u128) + &big(1), big(1 << 64));
+        assert_eq!(&big(1 << 64) - &big(1), big(u64::MAX as u128));
+        assert_eq!(&big(u64::MAX as u128) * &big(u64::MAX as u128), big(u64::MAX as u128 * u64::MAX as u128));
+
+        let beyond = &big(u128::MAX) + &big(1);
+        assert_eq!(beyond.bits(), 129);
+        assert_eq!(beyond.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(&beyond - &big(u128::MAX), big(1));
+    }
+
+    #[test]
+    fn shifts_match_u128() {
+        for &value in &samples() {
+            for bits in [0, 1, 31, 32, 33, 63, 64, 65] {
+                if value.leading_zeros() as usize >= bits {
+                    assert_eq!(&big(value) << bits, big(value << bits), "{} << {}", value, bits);
+                }
+                assert_eq!(&big(value) >> bits, big(value >> bits), "{} >> {}", value, bits);
+            }
+        }
+        assert_eq!(&big(1) >> 200, BigUint::zero());
+    }
+
+    #[test]
+    fn isqrt_of_squares_and_their_neighbours() {
+        for root in [1u128, 2, 3, 46_341, u32::MAX as u128, 1 << 32, u64::MAX as u128, (1 << 63) + 12_345] {
+            let square = root * root;
+            assert_eq!(big(square).isqrt(), big(root), "isqrt({})", square);
+            assert_eq!(big(square - 1).isqrt(), big(root - 1), "isqrt({} - 1)", square);
+            assert_eq!(big(square + 1).isqrt(), big(root), "isqrt({} + 1)", square);
+        }
+        assert_eq!(BigUint::zero().isqrt(), BigUint::zero());
+        assert_eq!(big(u128::MAX).isqrt(), big(u64::MAX as u128));
+        for &value in &samples() {
+            assert_eq!(big(value).isqrt(), big(value.isqrt()), "isqrt({})", value);
+        }
+    }
+
+    #[test]
+    fn decimal_round_trips() {
+        for &value in &samples() {
+            let text = value.to_string();
+            assert_eq!(big(value).to_string(), text);
+            assert_eq!(text.parse::<BigUint>(), Ok(big(value)));
+        }
+        assert_eq!(format!("{:>5}", big(42)), "   42");
+        assert_eq!(big(1_000_000_000).to_string(), "1000000000");
+
+        let long = "123456789012345678901234567890123456789012345678901234567890";
+        assert_eq!(long.parse::<BigUint>().unwrap().to_string(), long);
+    }
+
+    #[test]
+    fn only_plain_digits_parse() {
+        for text in ["", "-1", "+1", "1 2", "12a", " 7"] {
+            assert_eq!(text.parse::<BigUint>(), Err(ParseBigUintError { text: text.to_string() }), "{:?}", text);
+        }
+        assert_eq!("007".parse::<BigUint>(), Ok(big(7)));
+    }
+}
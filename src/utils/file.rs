@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 
 pub fn read_file(path: &str) -> io::Result<String> {
     let mut file = match File::open(path) {
@@ -11,4 +11,9 @@ pub fn read_file(path: &str) -> io::Result<String> {
         Ok(_) => Ok(contents),
         Err(error) => Err(error),
     }
-}
\ No newline at end of file
+}
+
+pub fn write_file(path: &str, contents: &str) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents.as_bytes())
+}
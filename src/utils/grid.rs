@@ -0,0 +1,225 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::utils::parse::ParseError;
+
+/// A cell position; rows grow downwards and columns to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Point {
+        Point { row, col }
+    }
+
+    /// The point shifted by `(d_row, d_col)`, or `None` if that leaves the first quadrant.
+    pub fn offset(&self, d_row: isize, d_col: isize) -> Option<Point> {
+        Some(Point {
+            row: self.row.checked_add_signed(d_row)?,
+            col: self.col.checked_add_signed(d_col)?,
+        })
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({},{})", self.row, self.col)
+    }
+}
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const SURROUNDING: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A rectangular map stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid { rows, cols, cells: vec![fill; rows * cols] }
+    }
+
+    /// Builds a grid from one line of text per row, converting every character with `cell`.
+    /// Rows must all be as wide as the first one; blank trailing lines are ignored.
+    pub fn parse_with(text: &str, cell: impl Fn(char) -> T) -> Result<Grid<T>, ParseError> {
+        let mut cells: Vec<T> = vec![];
+        let mut rows = 0;
+        let mut cols = 0;
+        let lines: Vec<&str> = text.lines().collect();
+        let used = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |last| last + 1);
+        for (index, line) in lines[..used].iter().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&cell));
+            let width = cells.len() - before;
+            if rows == 0 {
+                cols = width;
+            } else if width != cols {
+                return Err(ParseError {
+                    line: index + 1,
+                    column: width.min(cols) + 1,
+                    message: format!("row has {} cells, expected {}", width, cols),
+                });
+            }
+            rows += 1;
+        }
+        Ok(Grid { rows, cols, cells })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.row < self.rows && point.col < self.cols
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.contains(point) {
+            self.cells.get(point.row * self.cols + point.col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            self.cells.get_mut(point.row * self.cols + point.col)
+        } else {
+            None
+        }
+    }
+
+    /// In-bounds up, left, right and down neighbours of `point`.
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &ORTHOGONAL)
+    }
+
+    /// In-bounds neighbours of `point` including diagonals, row by row.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &SURROUNDING)
+    }
+
+    fn neighbours<'a>(&'a self, point: Point, offsets: &'a [(isize, isize)]) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&(d_row, d_col)| point.offset(d_row, d_col))
+            .filter(move |&p| self.contains(p))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max(1)` keeps `chunks` happy for a grid with no columns; there are no cells anyway.
+        self.cells.chunks(self.cols.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.cols.max(1)).take(self.rows)
+    }
+
+    pub fn iter_columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.cols).map(move |col| self.column(col))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| Point { row, col }))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(text: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_with(text, |c| c)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", point, self.rows, self.cols))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(point).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", point, rows, cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(iter: impl Iterator<Item = Point>) -> Vec<(usize, usize)> {
+        iter.map(|p| (p.row, p.col)).collect()
+    }
+
+    #[test]
+    fn parse_reads_rows_and_ignores_trailing_blank_lines() {
+        let grid = Grid::parse("ab.\n.cd\n\n\n").unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[Point::new(1, 2)], 'd');
+        assert_eq!(grid.row(0), &['a', 'b', '.']);
+        assert_eq!(grid.column(1).collect::<String>(), "bc");
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+
+        let empty = Grid::parse("").unwrap();
+        assert_eq!((empty.rows(), empty.cols()), (0, 0));
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        let error = Grid::parse("abc\nab\nabc\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.message, "row has 2 cells, expected 3");
+
+        let error = Grid::parse("ab\nab\n\nabcd\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.message, "row has 0 cells, expected 2");
+    }
+
+    #[test]
+    fn neighbours_stay_inside_the_grid() {
+        let grid = Grid::new(3, 4, '.');
+        assert_eq!(points(grid.neighbours4(Point::new(0, 0))), vec![(0, 1), (1, 0)]);
+        assert_eq!(points(grid.neighbours8(Point::new(0, 0))), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(points(grid.neighbours4(Point::new(2, 3))), vec![(1, 3), (2, 2)]);
+        assert_eq!(points(grid.neighbours8(Point::new(2, 3))), vec![(1, 2), (1, 3), (2, 2)]);
+        assert_eq!(points(grid.neighbours8(Point::new(0, 3))), vec![(0, 2), (1, 2), (1, 3)]);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours4(Point::new(1, 1)).count(), 4);
+
+        let single = Grid::new(1, 1, 0);
+        assert_eq!(single.neighbours8(Point::new(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn offset_refuses_to_leave_the_first_quadrant() {
+        assert_eq!(Point::new(0, 5).offset(-1, 0), None);
+        assert_eq!(Point::new(2, 0).offset(0, -1), None);
+        assert_eq!(Point::new(2, 3).offset(-2, 1), Some(Point::new(0, 4)));
+    }
+}
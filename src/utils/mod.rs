@@ -0,0 +1,5 @@
+pub mod big;
+pub mod file;
+pub mod grid;
+pub mod number;
+pub mod parse;
@@ -1,5 +1,191 @@
 use std::cmp;
+use std::ops::{Div, Mul, Rem};
 
-pub fn min_of_three(a: i32, b: i32, c: i32) -> i32 {
+/// Primitive integers, with the checked and saturating operations the days need.
+pub trait Integer: Copy + Ord {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+}
+
+/// Unsigned primitive integers, for `gcd`, `lcm` and `isqrt`.
+pub trait Unsigned: Integer + Div<Output = Self> + Mul<Output = Self> + Rem<Output = Self> {
+    fn isqrt(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> { <$t>::checked_add(self, rhs) }
+            fn checked_sub(self, rhs: Self) -> Option<Self> { <$t>::checked_sub(self, rhs) }
+            fn checked_mul(self, rhs: Self) -> Option<Self> { <$t>::checked_mul(self, rhs) }
+            fn saturating_add(self, rhs: Self) -> Self { <$t>::saturating_add(self, rhs) }
+            fn saturating_sub(self, rhs: Self) -> Self { <$t>::saturating_sub(self, rhs) }
+            fn saturating_mul(self, rhs: Self) -> Self { <$t>::saturating_mul(self, rhs) }
+        }
+    )*};
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl Unsigned for $t {
+            fn isqrt(self) -> Self { <$t>::isqrt(self) }
+        }
+    )*};
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+pub fn min_of_three<T: Ord>(a: T, b: T, c: T) -> T {
     cmp::min(a, cmp::min(b, c))
-}
\ No newline at end of file
+}
+
+pub fn min_of<T: Ord + Copy>(values: &[T]) -> Option<T> {
+    values.iter().copied().min()
+}
+
+pub fn max_of<T: Ord + Copy>(values: &[T]) -> Option<T> {
+    values.iter().copied().max()
+}
+
+/// Smallest and largest value of an iterator in a single pass.
+pub fn min_max<T: Ord + Copy, I: IntoIterator<Item = T>>(values: I) -> Option<(T, T)> {
+    values.into_iter().fold(None, |acc, v| match acc {
+        None => Some((v, v)),
+        Some((lo, hi)) => Some((cmp::min(lo, v), cmp::max(hi, v))),
+    })
+}
+
+/// Sum that returns `None` instead of overflowing.
+pub fn checked_sum<T: Integer, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values.into_iter().try_fold(T::ZERO, |acc, v| acc.checked_add(v))
+}
+
+/// Product that returns `None` instead of overflowing.
+pub fn checked_product<T: Integer, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, |acc, v| acc.checked_mul(v))
+}
+
+pub fn saturating_sum<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ZERO, |acc, v| acc.saturating_add(v))
+}
+
+pub fn saturating_product<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ONE, |acc, v| acc.saturating_mul(v))
+}
+
+pub fn gcd<T: Unsigned>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple; `lcm(0, n)` is 0. Panics on overflow in debug builds, like `*`.
+pub fn lcm<T: Unsigned>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    a / gcd(a, b) * b
+}
+
+pub fn checked_lcm<T: Unsigned>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of every value, or `None` on overflow. The lcm of nothing is 1.
+pub fn lcm_of<T: Unsigned, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, checked_lcm)
+}
+
+/// Largest `r` with `r * r <= n`.
+pub fn isqrt<T: Unsigned>(n: T) -> T {
+    n.isqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_and_max_of_slices_and_iterators() {
+        assert_eq!(min_of_three(3, 1, 2), 1);
+        assert_eq!(min_of(&[4, -2, 9]), Some(-2));
+        assert_eq!(max_of(&[4, -2, 9]), Some(9));
+        assert_eq!(min_of::<u8>(&[]), None);
+        assert_eq!(max_of::<u8>(&[]), None);
+        assert_eq!(min_max([5u32, 1, 8, 3]), Some((1, 8)));
+        assert_eq!(min_max("bca".chars()), Some(('a', 'c')));
+        assert_eq!(min_max(Vec::<u32>::new()), None);
+    }
+
+    #[test]
+    fn checked_folds_report_overflow() {
+        assert_eq!(checked_sum([1u8, 2, 3]), Some(6));
+        assert_eq!(checked_sum([u8::MAX, 1]), None);
+        assert_eq!(checked_sum([i8::MIN, -1]), None);
+        assert_eq!(checked_product([2u64, 3, 7]), Some(42));
+        assert_eq!(checked_product([1u64 << 32, 1 << 32]), None);
+        assert_eq!(checked_sum(Vec::<u32>::new()), Some(0));
+        assert_eq!(checked_product(Vec::<u32>::new()), Some(1));
+    }
+
+    #[test]
+    fn gcd_of_coprime_and_shared_factors() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(17u32, 5), 1);
+        assert_eq!(gcd(0u32, 7), 7);
+        assert_eq!(gcd(7u32, 0), 7);
+    }
+
+    #[test]
+    fn lcm_with_zero_is_zero() {
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(0u64, 6), 0);
+        assert_eq!(lcm(6u64, 0), 0);
+        assert_eq!(checked_lcm(0u64, 6), Some(0));
+    }
+
+    #[test]
+    fn checked_lcm_reports_overflow() {
+        assert_eq!(checked_lcm(u64::MAX, 2), None);
+        assert_eq!(checked_lcm(1u64 << 32, 1 << 32), Some(1 << 32));
+        assert_eq!(checked_lcm(u8::MAX, 2), None);
+        assert_eq!(lcm_of([2u64, 3, 4]), Some(12));
+        assert_eq!(lcm_of([u64::MAX, u64::MAX - 1]), None);
+        assert_eq!(lcm_of(Vec::<u64>::new()), Some(1));
+    }
+
+    #[test]
+    fn isqrt_near_u64_max() {
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        let root = u32::MAX as u64;
+        assert_eq!(isqrt(root * root), root);
+        assert_eq!(isqrt(root * root - 1), root - 1);
+        assert_eq!(isqrt(0u64), 0);
+        assert_eq!(isqrt(15u64), 3);
+        assert_eq!(isqrt(16u64), 4);
+    }
+
+    #[test]
+    fn saturating_folds_stop_at_the_limit() {
+        assert_eq!(saturating_sum([u8::MAX, 1]), u8::MAX);
+        assert_eq!(saturating_product([16u8, 16]), u8::MAX);
+        assert_eq!(saturating_sum(Vec::<u32>::new()), 0);
+        assert_eq!(saturating_product(Vec::<u32>::new()), 1);
+    }
+}
@@ -0,0 +1,249 @@
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::slice::Iter;
+use std::str::FromStr;
+
+/// Error raised while parsing puzzle input; `line` and `column` are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Walks a single line of input, producing `ParseError`s that point at the current column.
+pub struct Cursor<'a> {
+    text: &'a str,
+    line: usize,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(text: &'a str, line: usize) -> Cursor<'a> {
+        Cursor { text, line, pos: 0 }
+    }
+
+    /// Byte offset into the line, for rewinding with `seek`.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.text[..self.pos].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    /// Consumes `c` if it is the next character.
+    pub fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    pub fn skip_spaces(&mut self) {
+        while self.eat(' ') || self.eat('\t') {}
+    }
+
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.peek().filter(|&c| predicate(c)) {
+            self.pos += c.len_utf8();
+        }
+        &self.text[start..self.pos]
+    }
+
+    pub fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.text[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", literal)))
+        }
+    }
+
+    /// Reads a run of ASCII digits as any unsigned number type.
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(self.error("expected a number"));
+        }
+        digits.parse().map_err(|_| {
+            self.pos = start;
+            self.error(format!("number {} is too large", digits))
+        })
+    }
+
+    /// Reads a run of ASCII letters.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let word = self.take_while(|c| c.is_ascii_alphabetic());
+        if word.is_empty() {
+            return Err(self.error("expected a word"));
+        }
+        Ok(word)
+    }
+}
+
+/// The command-line arguments after a day's name, as its option handler sees them.
+pub struct Args<'a> {
+    iter: Peekable<Iter<'a, String>>,
+}
+
+impl<'a> Iterator for Args<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.iter.next().map(String::as_str)
+    }
+}
+
+impl<'a> Args<'a> {
+    /// The argument `option` takes, or an error saying it needs `what`.
+    pub fn value(&mut self, option: &str, what: &str) -> Result<&'a str, String> {
+        self.next().ok_or_else(|| format!("{} needs {}", option, what))
+    }
+
+    /// The next argument, parsed, or an error saying `option` needs `what`.
+    pub fn parsed<T: FromStr>(&mut self, option: &str, what: &str) -> Result<T, String> {
+        self.next().and_then(|value| value.parse().ok()).ok_or_else(|| format!("{} needs {}", option, what))
+    }
+
+    /// The next argument unless it is another `--` flag, for optional values.
+    pub fn next_value(&mut self) -> Option<&'a str> {
+        self.iter.next_if(|next| !next.starts_with("--")).map(String::as_str)
+    }
+}
+
+/// Walks a day's command line. `--input PATH` replaces `default_input`; every other argument
+/// goes to `option`, which returns `Ok(false)` for ones it does not know. Returns the input
+/// path, or the first problem as a message ready to print.
+pub fn parse_args(
+    args: &[String],
+    default_input: &str,
+    mut option: impl FnMut(&str, &mut Args) -> Result<bool, String>,
+) -> Result<String, String> {
+    let mut input = default_input.to_string();
+    let mut args = Args { iter: args.iter().peekable() };
+
+    while let Some(arg) = args.next() {
+        if arg == "--input" {
+            input = args.value(arg, "a path")?.to_string();
+        } else if !option(arg, &mut args)? {
+            return Err(format!("unknown option {}", arg));
+        }
+    }
+
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn cursor_columns_count_characters_from_one() {
+        let mut cursor = Cursor::new("Game 12: 3 blue", 4);
+        assert_eq!(cursor.error("start").column, 1);
+        cursor.expect("Game").unwrap();
+        cursor.skip_spaces();
+        assert_eq!(cursor.number::<u32>(), Ok(12));
+        assert_eq!(cursor.error("after the id").column, 8);
+
+        let error = cursor.expect(";").unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (4, 8, "expected ';'"));
+
+        // Columns are characters, not bytes.
+        let mut cursor = Cursor::new("é1x", 1);
+        cursor.eat('é');
+        cursor.number::<u8>().unwrap();
+        assert_eq!(cursor.error("here").column, 3);
+    }
+
+    #[test]
+    fn cursor_numbers_and_words() {
+        let mut cursor = Cursor::new("300 red", 1);
+        let error = cursor.number::<u8>().unwrap_err();
+        assert_eq!((error.column, error.message.as_str()), (1, "number 300 is too large"));
+        assert_eq!(cursor.number::<u16>(), Ok(300));
+        cursor.skip_spaces();
+        assert_eq!(cursor.number::<u8>().unwrap_err().message, "expected a number");
+        assert_eq!(cursor.word(), Ok("red"));
+        assert!(cursor.is_at_end());
+        assert_eq!(cursor.word().unwrap_err().message, "expected a word");
+    }
+
+    #[test]
+    fn parse_args_handles_input_and_options() {
+        let mut flag = false;
+        let mut count = 0;
+        let input = parse_args(&strings(&["--flag", "--input", "x.txt", "--count", "3"]), "default.txt", |arg, args| {
+            match arg {
+                "--flag" => flag = true,
+                "--count" => count = args.parsed(arg, "a number")?,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        });
+        assert_eq!(input, Ok("x.txt".to_string()));
+        assert!(flag);
+        assert_eq!(count, 3);
+
+        assert_eq!(parse_args(&[], "default.txt", |_, _| Ok(false)), Ok("default.txt".to_string()));
+    }
+
+    #[test]
+    fn parse_args_reports_unknown_options_and_missing_values() {
+        let count = |arg: &str, args: &mut Args| -> Result<bool, String> {
+            match arg {
+                "--count" => args.parsed::<u32>(arg, "a number").map(|_| true),
+                _ => Ok(false),
+            }
+        };
+        assert_eq!(parse_args(&strings(&["--bogus"]), "in.txt", count), Err("unknown option --bogus".to_string()));
+        assert_eq!(parse_args(&strings(&["--input"]), "in.txt", count), Err("--input needs a path".to_string()));
+        assert_eq!(parse_args(&strings(&["--count"]), "in.txt", count), Err("--count needs a number".to_string()));
+        assert_eq!(parse_args(&strings(&["--count", "x"]), "in.txt", count), Err("--count needs a number".to_string()));
+    }
+
+    #[test]
+    fn next_value_skips_flags() {
+        let args = strings(&["*#", "--html", "out.html"]);
+        let mut args = Args { iter: args.iter().peekable() };
+        assert_eq!(args.next_value(), Some("*#"));
+        assert_eq!(args.next_value(), None);
+        assert_eq!(args.next(), Some("--html"));
+        assert_eq!(args.next_value(), Some("out.html"));
+        assert_eq!(args.next_value(), None);
+    }
+}